@@ -0,0 +1,49 @@
+//! The configuration file of the CLI, `mitex.json` in the workspace by
+//! default.
+//!
+//! ```json
+//! {
+//!   "templates": {
+//!     "IEEEtran": {
+//!       "import": "ieee.typ",
+//!       "function": "ieee",
+//!       "args": { "bibliography-file": "\"refs.bib\"" }
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use mitex::{ConvertOptions, DocumentTemplate};
+
+/// The configuration of the CLI
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The Typst templates by the document classes, replacing the built-in
+    /// ones for `article`, `report` and `IEEEtran`
+    pub templates: HashMap<String, DocumentTemplate>,
+}
+
+impl Config {
+    /// Loads the configuration from a JSON file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file: {}", path.display()))?;
+        serde_json::from_str(&config)
+            .with_context(|| format!("failed to parse config file: {}", path.display()))
+    }
+
+    /// Applies the configuration to the options
+    pub fn apply(&self, mut opts: ConvertOptions) -> ConvertOptions {
+        for (class, template) in &self.templates {
+            opts = opts.template(class, template.clone());
+        }
+        opts
+    }
+}
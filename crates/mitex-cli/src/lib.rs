@@ -1,5 +1,6 @@
 //! The CLI for MiTeX.
 
+pub mod config;
 pub mod utils;
 mod version;
 
@@ -93,6 +94,55 @@ pub struct CompileArgs {
     #[clap(long, value_enum)]
     pub stage: Option<CompileStage>,
 
+    /// Continues converting after errors.
+    ///
+    /// The LaTeX code failed to convert is replaced by a highlighted
+    /// `mitex-error(..)` placeholder, and all errors are printed.
+    ///
+    /// ## Example
+    ///
+    /// ```bash
+    /// mitex compile --recover main.tex
+    /// ```
+    #[clap(long)]
+    pub recover: bool,
+
+    /// Writes a source map from the output to the input as a sidecar file,
+    /// which is the output file name with `.map.json` extension.
+    ///
+    /// ## Example
+    ///
+    /// ```bash
+    /// mitex compile --source-map main.tex
+    /// ```
+    #[clap(long)]
+    pub source_map: bool,
+
+    /// Path to the configuration file, default to `mitex.json` in the
+    /// workspace if it exists.
+    ///
+    /// The file maps the document classes to Typst templates, replacing the
+    /// built-in ones for `article`, `report` and `IEEEtran`.
+    ///
+    /// ## Example
+    ///
+    /// ```bash
+    /// mitex compile --config mitex.json main.tex
+    /// ```
+    ///
+    /// With the configuration below, `\documentclass{IEEEtran}` wraps the
+    /// document by `#show: ieee.with(..)` imported from `ieee.typ`.
+    ///
+    /// ```json
+    /// {
+    ///   "templates": {
+    ///     "IEEEtran": { "import": "ieee.typ", "function": "ieee" }
+    ///   }
+    /// }
+    /// ```
+    #[clap(long)]
+    pub config: Option<String>,
+
     /// Output to file, default to entry file name with `.typ` extension.
     ///
     /// ## Example
@@ -14,9 +14,9 @@ use std::path::Path;
 use std::process::exit;
 
 use anyhow::Context;
-use mitex_spec::{CmdShape, CommandSpecItem, EnvShape};
 use serde::{Deserialize, Serialize};
 
+use mitex_cli::config::Config;
 use mitex_cli::utils::{Error, UnwrapOrExit};
 use mitex_cli::{
     get_cli, get_os_opts, intercept_version, CompileStage, CompletionArgs, SpecSubCommands,
@@ -36,10 +36,14 @@ fn main() -> anyhow::Result<()> {
 
     match opts.sub {
         Some(Subcommands::Compile(args)) => {
+            let config = load_config(&args.workspace, args.config.as_deref()).unwrap_or_exit();
             compile(
                 &args.input,
                 &args.output,
                 matches!(args.stage, Some(CompileStage::Syntax)),
+                args.recover,
+                args.source_map,
+                &config,
             )
             .unwrap_or_exit();
             exit(0);
@@ -69,64 +73,90 @@ fn main() -> anyhow::Result<()> {
     }
 }
 
-fn compile(input_path: &str, output_path: &str, is_ast: bool) -> Result<(), Error> {
+/// Loads the given configuration file, or `mitex.json` in the workspace if it
+/// exists
+fn load_config(workspace: &str, path: Option<&str>) -> Result<Config, Error> {
+    let path = match path {
+        Some(path) => Path::new(path).to_owned(),
+        None => {
+            let path = Path::new(workspace).join("mitex.json");
+            if !path.exists() {
+                return Ok(Config::default());
+            }
+            path
+        }
+    };
+    Ok(Config::load(&path)?)
+}
+
+fn compile(
+    input_path: &str,
+    output_path: &str,
+    is_ast: bool,
+    recover: bool,
+    emit_source_map: bool,
+    config: &Config,
+) -> Result<(), Error> {
     let input = std::fs::read_to_string(input_path)
         .with_context(|| format!("failed to read input file: {input_path}"))?;
 
-    let spec = DEFAULT_SPEC.clone();
-
-    let output = if !is_ast {
-        mitex::convert_text(&input, Some(spec.clone())).map_err(|e| anyhow::anyhow!("{}", e))
-    } else {
+    let mut source_map = None;
+    let mut bib_items = vec![];
+    // entries of `thebibliography` are loaded from a Hayagriva sidecar file
+    let bib_path = Path::new(output_path).with_extension("bib.yml");
+    let output = if is_ast {
         Ok(format!(
             "{:#?}",
             mitex_parser::parse(&input, DEFAULT_SPEC.clone())
         ))
+    } else {
+        let opts = mitex::ConvertOptions::new(mitex::LaTeXMode::Text)
+            .emit_prelude(true)
+            .document(true)
+            .recover(recover)
+            .source_map(emit_source_map)
+            .bibliography_file(bib_path.file_name().unwrap().to_string_lossy());
+        let opts = config.apply(opts);
+        let mut converted = mitex::convert(&input, &opts);
+        source_map = converted.source_map.take();
+        bib_items = std::mem::take(&mut converted.bib_items);
+        // errors stop the conversion unless recovering, while warnings are
+        // always printed
+        for diag in &converted.diagnostics {
+            if recover || !diag.is_error() {
+                let range = &diag.range;
+                eprintln!(
+                    "{input_path}:{}..{}: {diag} [{}]",
+                    range.start, range.end, diag.code
+                );
+            }
+        }
+        if recover {
+            Ok(converted.output)
+        } else {
+            converted
+                .into_result()
+                .map_err(|e| anyhow::anyhow!("{}", e))
+        }
     };
 
     let output = output.with_context(|| format!("failed to convert input file: {input_path}"))?;
-
-    // Insert preludes
-    // todo: better way?
-    let mut builtin_set = std::collections::HashSet::<&'static str>::new();
-    builtin_set.extend(["and", "or", "in", "not"]);
-    let mut alias_set = std::collections::HashSet::<Box<str>>::new();
-    for (_, cmd) in spec.items() {
-        let alias = match cmd {
-            CommandSpecItem::Cmd(CmdShape {
-                alias: Some(alias), ..
-            }) => alias.as_str(),
-            CommandSpecItem::Env(EnvShape {
-                alias: Some(alias), ..
-            }) => alias.as_str(),
-            _ => continue,
-        };
-        if alias.is_empty() || !alias.chars().all(|c| c.is_ascii_alphanumeric()) {
-            continue;
-        }
-        if builtin_set.contains(alias) || alias_set.contains(alias) {
-            continue;
-        }
-        alias_set.insert(alias.into());
+    std::fs::write(output_path, output)?;
+
+    // Write the source map as a sidecar file
+    if let Some(source_map) = source_map {
+        let map_path = Path::new(output_path).with_extension("map.json");
+        let source_map =
+            serde_json::to_string(&source_map).context("failed to serialize source map")?;
+        std::fs::write(&map_path, source_map)
+            .with_context(|| format!("failed to write source map: {}", map_path.display()))?;
     }
 
-    let mut alias_set = alias_set.into_iter().collect::<Vec<_>>();
-    alias_set.sort();
-    let preludes_str = alias_set
-        .into_iter()
-        .map(|alias| format!(r#"#let {alias} = mitex-scope.at("{alias}", default: none);"#))
-        .collect::<Vec<_>>()
-        .join("\n");
-    std::fs::write(
-        output_path,
-        format!(
-            r#"
-#import "@preview/mitex:0.2.5": *
-{preludes_str}
-
-{output}"#
-        ),
-    )?;
+    // Write the entries of `thebibliography` as a sidecar file
+    if !bib_items.is_empty() {
+        std::fs::write(&bib_path, mitex::hayagriva_yaml(&bib_items))
+            .with_context(|| format!("failed to write bibliography: {}", bib_path.display()))?;
+    }
 
     Ok(())
 }
@@ -86,7 +86,7 @@ impl<'a, S: TokenStream<'a>> Lexer<'a, S> {
     /// Note that since we have a bumper, the returning string is not always
     /// sliced from the input
     pub fn new_with_bumper(input: &'a str, spec: CommandSpec, bumper: S) -> Self {
-        let inner = Token::lexer_with_extras(input, (spec, 0..0));
+        let inner = Token::lexer_with_extras(input, (spec, 0..0, None));
         let mut n = Self {
             ctx: StreamContext {
                 inner,
@@ -1,5 +1,6 @@
 use logos::Source;
 
+use crate::token::lex_verbatim;
 use crate::{BraceKind, CommandName, Tok, Token};
 
 /// Lex Cache for bundling (bumping) lexing operations for CPU locality
@@ -79,7 +80,22 @@ pub struct StreamContext<'a> {
 impl<'a> StreamContext<'a> {
     #[inline]
     pub fn lex_one(l: &mut logos::Lexer<'a, Token>) -> Option<Tok<'a>> {
+        // lex the raw text of a verbatim command or environment once reached
+        if let Some(raw) = l.extras.2.clone() {
+            let start = l.span().end;
+            if start >= raw.start {
+                l.extras.2 = None;
+                if start < raw.end {
+                    l.bump(raw.end - start);
+                    return Some((Token::Verbatim, l.source().slice(start..raw.end).unwrap()));
+                }
+            }
+        }
+
         let tok = l.next()?.unwrap();
+        if let Some(raw) = lex_verbatim(l, tok) {
+            l.extras.2 = Some(raw);
+        }
 
         let source_text = match tok {
             Token::CommandName(CommandName::BeginEnvironment | CommandName::EndEnvironment) => {
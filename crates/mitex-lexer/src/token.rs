@@ -1,5 +1,8 @@
 use logos::{Logos, Source};
-use mitex_spec::CommandSpec;
+use mitex_spec::argument_kind::{
+    ARGUMENT_KIND_BRACKET, ARGUMENT_KIND_TERM, ARGUMENT_KIND_VERBATIM,
+};
+use mitex_spec::{ArgPattern, ArgShape, CommandSpec, ContextFeature};
 
 /// Brace kinds in TeX, used by defining [`Token`]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
@@ -17,8 +20,12 @@ pub enum BraceKind {
 /// For naming of marks, see <https://en.wikipedia.org/wiki/List_of_typographical_symbols_and_punctuation_marks>
 ///
 /// It also specifies how logos would lex the token
+///
+/// The extras of the lexer are the command specification, the span of the
+/// last environment name, and the span of the raw text to lex as
+/// [`Token::Verbatim`]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Logos)]
-#[logos(extras = (CommandSpec, logos::Span))]
+#[logos(extras = (CommandSpec, logos::Span, Option<logos::Span>))]
 pub enum Token {
     /// A line break
     /// Typically a `\r\n` or `\n`
@@ -123,6 +130,12 @@ pub enum Token {
     #[regex(r"\\", lex_command_name, priority = 3)]
     CommandName(CommandName),
 
+    /// A raw text of a verbatim command or environment
+    /// E.g. `|a_b|` of `\verb|a_b|`, or the body of `\begin{verbatim}`
+    ///
+    /// It is not lexed by logos, see [`lex_verbatim`]
+    Verbatim,
+
     /// Macro error
     Error,
 
@@ -343,6 +356,91 @@ fn advance_ascii_name(
     bump_size
 }
 
+/// Find the raw text following a verbatim command like `\verb` or the
+/// beginning of a verbatim environment like `verbatim`, which is lexed as a
+/// [`Token::Verbatim`] when the lexer reaches it
+pub(crate) fn lex_verbatim(lexer: &logos::Lexer<Token>, tok: Token) -> Option<logos::Span> {
+    let spec = &lexer.extras.0;
+    let offset = lexer.span().end;
+    let rest = &lexer.source()[offset..];
+    let range = match tok {
+        Token::CommandName(CommandName::BeginEnvironment) => {
+            let name = lexer.source().slice(lexer.extras.1.clone())?;
+            let env = spec.get_env(name)?;
+            if !matches!(env.ctx_feature, ContextFeature::IsVerbatim) {
+                return None;
+            }
+            // the arguments are lexed as usual until the end of line
+            let start = match env.args {
+                ArgPattern::None => 0,
+                _ => {
+                    let line_end = rest.find(['\r', '\n'])?;
+                    let line_breaks = rest[line_end..].bytes();
+                    line_end
+                        + line_breaks
+                            .take_while(|c| matches!(c, b'\r' | b'\n'))
+                            .count()
+                }
+            };
+            let end = rest.find(&format!("\\end{{{name}}}")).unwrap_or(rest.len());
+            start..end
+        }
+        Token::CommandName(CommandName::Generic) => {
+            let name = &lexer.slice()[1..];
+            let ArgShape::Right {
+                pattern: ArgPattern::Glob { pattern },
+            } = &spec.get_cmd(name)?.args
+            else {
+                return None;
+            };
+            let leading = &pattern.0[..pattern.0.find(ARGUMENT_KIND_VERBATIM)?];
+            let mut terms = leading.matches(ARGUMENT_KIND_TERM).count();
+            let brackets = leading.contains(ARGUMENT_KIND_BRACKET);
+            // the preceding arguments are lexed as usual
+            let mut start = 0;
+            loop {
+                match rest[start..].chars().next()? {
+                    '[' if brackets => start += balanced_len(&rest[start..], b'[', b']')?,
+                    '{' if terms > 0 => {
+                        start += balanced_len(&rest[start..], b'{', b'}')?;
+                        terms -= 1;
+                    }
+                    _ => break,
+                }
+            }
+            let len = match rest[start..].chars().next()? {
+                '{' => balanced_len(&rest[start..], b'{', b'}')?,
+                delim if delim.is_whitespace() => return None,
+                delim => {
+                    let body = &rest[start + delim.len_utf8()..];
+                    let body_len = body.find(delim)?;
+                    body_len + delim.len_utf8() * 2
+                }
+            };
+            start..start + len
+        }
+        _ => return None,
+    };
+    (range.start < range.end).then(|| offset + range.start..offset + range.end)
+}
+
+/// The length of a group like `{a{b}}` at the start of the text, including
+/// the delimiters
+fn balanced_len(text: &str, open: u8, close: u8) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, c) in text.bytes().enumerate() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(index + 1);
+            }
+        }
+    }
+    None
+}
+
 fn lex_begin_end(lexer: &mut logos::Lexer<Token>, is_begin: bool) -> CommandName {
     struct LexTask<'a, 'b> {
         lexer: &'a mut logos::Lexer<'b, Token>,
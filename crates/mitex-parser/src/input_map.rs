@@ -0,0 +1,59 @@
+use std::ops::Range;
+
+/// The map from the ranges in the syntax tree to the ranges in the input
+///
+/// The syntax tree holds the text of the tokens in order, but not all of the
+/// input, e.g. it drops `\begin{` and `}` around the name of an environment,
+/// and it holds the tokens expanded from macros. An offset between two tokens
+/// in the input is mapped relative to the token before it.
+#[derive(Debug, Clone, Default)]
+pub struct InputMap {
+    /// The ranges of the tokens in the syntax tree and in the input, sorted by
+    /// the ranges in the syntax tree
+    tokens: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl InputMap {
+    pub(crate) fn new(tokens: Vec<(Range<usize>, Range<usize>)>) -> Self {
+        Self { tokens }
+    }
+
+    /// Maps a range in the syntax tree to the range in the input
+    pub fn range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.start(range.start);
+        let end = self.end(range.end).max(start);
+        start..end
+    }
+
+    /// Maps the start of a range, which belongs to the token starting at or
+    /// before it
+    fn start(&self, offset: usize) -> usize {
+        let index = self
+            .tokens
+            .partition_point(|(tree, _)| tree.start <= offset);
+        match index.checked_sub(1).map(|index| &self.tokens[index]) {
+            Some((tree, input)) if offset == tree.start => input.start,
+            Some((tree, input)) => map_in(tree, input, offset),
+            None => offset,
+        }
+    }
+
+    /// Maps the end of a range, which belongs to the token starting before it
+    fn end(&self, offset: usize) -> usize {
+        let index = self.tokens.partition_point(|(tree, _)| tree.start < offset);
+        match index.checked_sub(1).map(|index| &self.tokens[index]) {
+            Some((tree, input)) => map_in(tree, input, offset),
+            None => offset,
+        }
+    }
+}
+
+/// Maps an offset after the start of a token, where the end of the token is
+/// mapped to the end of its lexeme
+fn map_in(tree: &Range<usize>, input: &Range<usize>, offset: usize) -> usize {
+    if offset >= tree.end {
+        input.end + (offset - tree.end)
+    } else {
+        (input.start + (offset - tree.start)).min(input.end)
+    }
+}
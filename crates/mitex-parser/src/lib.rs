@@ -17,6 +17,7 @@
 //! modify the AST syntactically.
 
 mod arg_match;
+mod input_map;
 mod parser;
 pub mod syntax;
 
@@ -25,6 +26,8 @@ pub use spec::preludes::command as command_preludes;
 pub use spec::*;
 use syntax::SyntaxNode;
 
+pub use input_map::InputMap;
+
 use parser::Parser;
 
 /// Parse the input text with the given command specification
@@ -39,3 +42,23 @@ pub fn parse(input: &str, spec: CommandSpec) -> SyntaxNode {
 pub fn parse_without_macro(input: &str, spec: CommandSpec) -> SyntaxNode {
     SyntaxNode::new_root(Parser::new(input, spec).parse())
 }
+
+/// Parse the input text like [`parse`], also returning the map from the
+/// ranges in the syntax tree to the ranges in the input
+///
+/// The ranges differ since the syntax tree drops some text of the input, e.g.
+/// `\begin{` and `}` around the name of an environment.
+pub fn parse_with_input_map(input: &str, spec: CommandSpec) -> (SyntaxNode, InputMap) {
+    let (green, input_map) = Parser::new_macro(input, spec).parse_with_input_map();
+    (SyntaxNode::new_root(green), input_map)
+}
+
+/// Parse the input text like [`parse_without_macro`], also returning the map
+/// from the ranges in the syntax tree to the ranges in the input
+pub fn parse_without_macro_with_input_map(
+    input: &str,
+    spec: CommandSpec,
+) -> (SyntaxNode, InputMap) {
+    let (green, input_map) = Parser::new(input, spec).parse_with_input_map();
+    (SyntaxNode::new_root(green), input_map)
+}
@@ -1,6 +1,9 @@
+use std::ops::Range;
+
 use rowan::{Checkpoint, GreenNode, GreenNodeBuilder};
 
 use crate::arg_match::{ArgMatcher, ArgMatcherBuilder};
+use crate::input_map::InputMap;
 use crate::spec::argument_kind::*;
 use crate::syntax::SyntaxKind::{self, *};
 use crate::{ArgPattern, ArgShape, CommandSpec};
@@ -111,6 +114,12 @@ pub struct Parser<'a, S: TokenStream<'a> = ()> {
     lexer: Lexer<'a, S>,
     /// Helper for building syntax tree
     builder: GreenNodeBuilder<'static>,
+    /// The input text
+    input: &'a str,
+    /// The length of the text attached to the syntax tree
+    tree_len: usize,
+    /// The ranges of the tokens in the syntax tree and in the input
+    input_map: Vec<(Range<usize>, Range<usize>)>,
 
     /// Command specification
     spec: CommandSpec,
@@ -131,6 +140,9 @@ impl<'a> Parser<'a> {
         Self {
             lexer: Lexer::new(text, spec.clone()),
             builder: GreenNodeBuilder::new(),
+            input: text,
+            tree_len: 0,
+            input_map: Vec::new(),
             spec,
             arg_matchers: ArgMatcherBuilder::default(),
             list_state: Default::default(),
@@ -144,6 +156,9 @@ impl<'a> Parser<'a> {
         Parser::<'a, MacroEngine<'a>> {
             lexer,
             builder: GreenNodeBuilder::new(),
+            input: text,
+            tree_len: 0,
+            input_map: Vec::new(),
             spec,
             arg_matchers: ArgMatcherBuilder::default(),
             list_state: Default::default(),
@@ -191,7 +206,36 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
     /// Consume the next token and attach it to the syntax tree
     fn eat(&mut self) {
         let (kind, text) = self.lexer.eat().unwrap();
-        let kind: SyntaxKind = kind.into();
+        // the token of an environment only holds its name
+        let lexeme = matches!(
+            kind,
+            Token::CommandName(CommandName::BeginEnvironment | CommandName::EndEnvironment)
+        );
+        self.token(kind.into(), text, lexeme);
+    }
+
+    /// Attach a token to the syntax tree, recording its range in the input if
+    /// the text is a slice of the input, e.g. not from a macro definition
+    /// outside of it
+    ///
+    /// If `lexeme` is true, the range is extended to the whole lexeme like
+    /// `\begin{name}` around the text `name`.
+    fn token(&mut self, kind: SyntaxKind, text: &str, lexeme: bool) {
+        let tree = self.tree_len..self.tree_len + text.len();
+        self.tree_len = tree.end;
+        let start = (text.as_ptr() as usize).wrapping_sub(self.input.as_ptr() as usize);
+        let in_input = start
+            .checked_add(text.len())
+            .is_some_and(|end| end <= self.input.len());
+        if !text.is_empty() && in_input {
+            let mut input = start..start + text.len();
+            if lexeme {
+                input.start = self.input[..input.start].rfind('\\').unwrap_or(input.start);
+                let close = self.input[input.end..].find('}');
+                input.end += close.map_or(0, |close| close + 1);
+            }
+            self.input_map.push((tree, input));
+        }
         self.builder.token(kind.into(), text);
     }
 
@@ -206,7 +250,7 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
     /// syntax kind
     fn eat_as(&mut self, kind: SyntaxKind) {
         let (_, text) = self.lexer.eat().unwrap();
-        self.builder.token(kind.into(), text);
+        self.token(kind, text, false);
     }
 
     /// Lexer Interface
@@ -230,17 +274,16 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
 
     /// Lexer Interface
     fn extract_holding_trivia(&mut self) {
-        for (kind, text) in self.trivia_buffer.drain(..) {
-            let kind: SyntaxKind = kind.into();
-            self.builder.token(kind.into(), text);
+        for (kind, text) in std::mem::take(&mut self.trivia_buffer) {
+            self.token(kind.into(), text, false);
         }
     }
 
     /// Lexer Interface
     fn single_char(&mut self) -> Option<()> {
-        let first_char = self.lexer.peek_char()?;
-        self.builder
-            .token(TokenWord.into(), &first_char.to_string());
+        let text = self.lexer.peek_text()?;
+        let first_char = text.chars().next()?;
+        self.token(TokenWord, &text[..first_char.len_utf8()], false);
         self.lexer.consume_utf8_bytes(first_char.len_utf8());
 
         Some(())
@@ -256,11 +299,18 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
 
     /// Entry point
     /// The main entry point of the parser
-    pub fn parse(mut self) -> GreenNode {
+    pub fn parse(self) -> GreenNode {
+        self.parse_with_input_map().0
+    }
+
+    /// Entry point
+    /// Parse the input, also returning the map from the ranges in the syntax
+    /// tree to the ranges in the input
+    pub fn parse_with_input_map(mut self) -> (GreenNode, InputMap) {
         self.builder.start_node(ScopeRoot.into());
         self.item_list(ParseScope::Root);
         self.builder.finish_node();
-        self.builder.finish()
+        (self.builder.finish(), InputMap::new(self.input_map))
     }
 
     /// Parsing Helper
@@ -380,6 +430,7 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
             | Token::Hash
             | Token::Asterisk
             | Token::AtSign
+            | Token::Verbatim
             | Token::Error => {
                 self.eat();
                 return false;
@@ -704,18 +755,18 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
                 Token::Word if !GREEDY => {
                     // Split the word into single characters for term matching
                     let mut split_cnt = 0usize;
-                    for c in self.lexer.peek_text().unwrap().chars() {
+                    let text = self.lexer.peek_text().unwrap();
+                    for c in text.chars() {
                         if !searcher.try_match(ARGUMENT_KIND_TERM) {
                             if split_cnt > 0 {
                                 self.lexer.consume_utf8_bytes(split_cnt);
                             }
                             return;
                         }
+                        let char_text = &text[split_cnt..split_cnt + c.len_utf8()];
                         split_cnt += c.len_utf8();
 
-                        arg::<GREEDY, _, _>(self, |this| {
-                            this.builder.token(TokenWord.into(), &c.to_string())
-                        });
+                        arg::<GREEDY, _, _>(self, |this| this.token(TokenWord, char_text, false));
                     }
 
                     if !k_wrap_args!() {
@@ -761,7 +812,11 @@ impl<'a, S: TokenStream<'a>> Parser<'a, S> {
                 }
                 // rest of any item
                 kind => {
-                    if self.stop_by_scope(kind) || !searcher.try_match(ARGUMENT_KIND_TERM) {
+                    let encoded = match kind {
+                        Token::Verbatim => ARGUMENT_KIND_VERBATIM,
+                        _ => ARGUMENT_KIND_TERM,
+                    };
+                    if self.stop_by_scope(kind) || !searcher.try_match(encoded) {
                         return;
                     }
 
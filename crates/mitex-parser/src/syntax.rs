@@ -77,6 +77,7 @@ pub enum SyntaxKind {
     TokenDitto,
     TokenSemicolon,
     TokenCommandSym,
+    TokenVerbatim,
 
     // Clauses
     ClauseCommandName,
@@ -131,6 +132,7 @@ impl From<Token> for SyntaxKind {
             Token::Asterisk => SyntaxKind::TokenAsterisk,
             Token::AtSign => SyntaxKind::TokenAtSign,
             Token::NewLine => SyntaxKind::ItemNewLine,
+            Token::Verbatim => SyntaxKind::TokenVerbatim,
             Token::MacroArg(_) => SyntaxKind::TokenWord,
             Token::CommandName(
                 CommandName::ErrorBeginEnvironment | CommandName::ErrorEndEnvironment,
@@ -46,6 +46,9 @@ mod ast {
     #[cfg(test)]
     mod tabular;
 
+    #[cfg(test)]
+    mod verbatim;
+
     /// Convenient function to launch/debug a test case
     #[test]
     fn bug_playground() {}
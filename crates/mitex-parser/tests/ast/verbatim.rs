@@ -0,0 +1,65 @@
+use super::prelude::*;
+
+#[test]
+fn verb() {
+    assert_debug_snapshot!(parse(r#"\verb|a_{b| \lstinline[language=C]{x}"#), @r###"
+    root
+    |cmd
+    ||cmd-name("\\verb")
+    ||args(verbatim'("|a_{b|"))
+    |space'(" ")
+    |cmd
+    ||cmd-name("\\lstinline")
+    ||args
+    |||bracket
+    ||||lbracket'("[")
+    ||||text(word'("language=C"))
+    ||||rbracket'("]")
+    ||args(verbatim'("{x}"))
+    "###);
+}
+
+#[test]
+fn verbatim() {
+    assert_debug_snapshot!(parse(r#"\begin{lstlisting}[language=C]
+\end{x} $
+\end{lstlisting}"#), @r###"
+    root
+    |env
+    ||begin
+    |||sym'("lstlisting")
+    |||args
+    ||||bracket
+    |||||lbracket'("[")
+    |||||text(word'("language=C"))
+    |||||rbracket'("]")
+    ||br'("\n")
+    ||verbatim'("\\end{x} $\n")
+    ||end(sym'("lstlisting"))
+    "###);
+    assert_debug_snapshot!(parse(r#"\begin{verbatim}\end{x}"#), @r###"
+    root
+    |env
+    ||begin(sym'("verbatim"))
+    ||verbatim'("\\end{x}")
+    "###);
+}
+
+#[test]
+fn url() {
+    assert_debug_snapshot!(parse(r#"\href{a_b%c#d}{x} \url{e%f}"#), @r###"
+    root
+    |cmd
+    ||cmd-name("\\href")
+    ||args(verbatim'("{a_b%c#d}"))
+    ||args
+    |||curly
+    ||||lbrace'("{")
+    ||||text(word'("x"))
+    ||||rbrace'("}")
+    |space'(" ")
+    |cmd
+    ||cmd-name("\\url")
+    ||args(verbatim'("{e%f}"))
+    "###);
+}
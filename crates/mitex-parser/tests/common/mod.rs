@@ -84,6 +84,7 @@ pub mod ast_snapshot {
                 SyntaxKind::TokenDitto => "ditto'",
                 SyntaxKind::TokenSemicolon => "semicolon'",
                 SyntaxKind::TokenCommandSym => "sym'",
+                SyntaxKind::TokenVerbatim => "verbatim'",
                 SyntaxKind::ClauseCommandName => "cmd-name",
                 SyntaxKind::ClauseArgument => "args",
                 SyntaxKind::ClauseLR => "clause-lr",
@@ -99,6 +99,18 @@ impl CommandSpec {
         Self(Arc::new(CommandSpecRepr { commands }))
     }
 
+    /// Create a command specification by overlaying `layers` on `base` in
+    /// order. An item in a layer adds to or overrides the item with the same
+    /// name in `base` and the earlier layers.
+    pub fn overlay(base: &CommandSpec, layers: &[CommandSpec]) -> Self {
+        let mut commands = base.0.commands.clone();
+        for layer in layers {
+            let items = layer.0.commands.iter();
+            commands.extend(items.map(|(name, item)| (name.clone(), item.clone())));
+        }
+        Self::new(commands)
+    }
+
     /// Get an item by name
     pub fn get(&self, name: &str) -> Option<&CommandSpecItem> {
         self.0.commands.get(name)
@@ -170,6 +182,9 @@ pub mod argument_kind {
     pub const ARGUMENT_KIND_BRACKET: char = 'b';
     /// The character used for matching argument in a parenthesis group
     pub const ARGUMENT_KIND_PAREN: char = 'p';
+    /// The character used for matching a verbatim argument, which is lexed as
+    /// raw text, e.g. `|a_b|` of `\verb|a_b|`
+    pub const ARGUMENT_KIND_VERBATIM: char = 'v';
 }
 
 /// A shared string that represents a glob pattern.
@@ -226,6 +241,9 @@ mod glob_str_impl {
 /// - Bracket/b: []
 /// - Parenthesis/p: ()
 /// - Term/t: any remaining terms, typically {} or a single char
+/// - Verbatim/v: a raw text in braces or between a pair of the same char,
+///   e.g. `\verb|a_b|`, whose preceding arguments must be in braces or
+///   brackets
 ///
 /// Note: any prefix of the argument pattern are matched during the parse stage,
 /// so you need to check whether it is complete in later stages.
@@ -339,6 +357,9 @@ pub enum ContextFeature {
     /// Parse content like cases
     #[cfg_attr(feature = "serde", serde(rename = "is-cases"))]
     IsCases,
+    /// Parse content like aligned equations, where each row is numbered
+    #[cfg_attr(feature = "serde", serde(rename = "is-align"))]
+    IsAlign,
     /// Parse content like figure
     #[cfg_attr(feature = "serde", serde(rename = "is-figure"))]
     IsFigure,
@@ -351,4 +372,11 @@ pub enum ContextFeature {
     /// Parse content like enumerate
     #[cfg_attr(feature = "serde", serde(rename = "is-enumerate"))]
     IsEnumerate,
+    /// Parse content like description, where each item has a term
+    #[cfg_attr(feature = "serde", serde(rename = "is-description"))]
+    IsDescription,
+    /// Lex content as raw text like verbatim, where the arguments must be on
+    /// the line of `\begin`
+    #[cfg_attr(feature = "serde", serde(rename = "is-verbatim"))]
+    IsVerbatim,
 }
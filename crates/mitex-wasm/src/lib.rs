@@ -56,6 +56,41 @@ mod impls {
     pub fn convert_text(input: &str, spec: &[u8]) -> Result<String, String> {
         mitex::convert_text(input, extract_spec(spec))
     }
+
+    /// Encodes the output and the source map of a conversion into json like
+    /// `{ "output": "..", "source_map": [{ "output": .., "input": .. }] }`.
+    fn with_source_map(converted: mitex::Converted) -> Result<String, String> {
+        if let Some(err) = converted.diagnostics.iter().find(|d| d.is_error()) {
+            return Err(err.to_string());
+        }
+        let res = serde_json::json!({
+            "output": converted.output,
+            "source_map": converted.source_map,
+        });
+        Result::Ok(res.to_string())
+    }
+
+    /// Converts a LaTeX math equation like [`convert_math`], along with a
+    /// source map from the output to the input, encoded as json.
+    #[cfg_attr(feature = "web", wasm_bindgen)]
+    pub fn convert_math_with_source_map(input: &str, spec: &[u8]) -> Result<String, String> {
+        with_source_map(mitex::convert_math_with_source_map(
+            input,
+            extract_spec(spec),
+            false,
+        ))
+    }
+
+    /// Converts a LaTeX code like [`convert_text`], along with a source map
+    /// from the output to the input, encoded as json.
+    #[cfg_attr(feature = "web", wasm_bindgen)]
+    pub fn convert_text_with_source_map(input: &str, spec: &[u8]) -> Result<String, String> {
+        with_source_map(mitex::convert_text_with_source_map(
+            input,
+            extract_spec(spec),
+            false,
+        ))
+    }
 }
 
 /// Wrappers for Typst as the host
@@ -95,6 +130,28 @@ mod wasm_host {
         let res = super::impls::convert_text(input, spec)?;
         Result::Ok(res.into_bytes())
     }
+
+    /// See [`super::impls::convert_math_with_source_map`]
+    ///
+    /// # Errors
+    /// Returns an error if the input is not a valid utf-8 string
+    #[cfg_attr(feature = "typst-plugin", wasm_func)]
+    pub fn convert_math_with_source_map(input: &[u8], spec: &[u8]) -> Result<Vec<u8>, String> {
+        let input = wasm_into_str(input)?;
+        let res = super::impls::convert_math_with_source_map(input, spec)?;
+        Result::Ok(res.into_bytes())
+    }
+
+    /// See [`super::impls::convert_text_with_source_map`]
+    ///
+    /// # Errors
+    /// Returns an error if the input is not a valid utf-8 string
+    #[cfg_attr(feature = "typst-plugin", wasm_func)]
+    pub fn convert_text_with_source_map(input: &[u8], spec: &[u8]) -> Result<Vec<u8>, String> {
+        let input = wasm_into_str(input)?;
+        let res = super::impls::convert_text_with_source_map(input, spec)?;
+        Result::Ok(res.into_bytes())
+    }
 }
 
 /// Wrappers for Browsers as the host
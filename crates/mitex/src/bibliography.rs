@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+/// An entry of an inline `thebibliography` environment, i.e. a `\bibitem`
+/// along with the text following it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BibItem {
+    /// The citation key, e.g. `knuth84` in `\bibitem{knuth84}`
+    pub key: String,
+    /// The custom label, e.g. `Knu84` in `\bibitem[Knu84]{knuth84}`
+    pub label: Option<String>,
+    /// The text of the entry with the markup stripped
+    pub text: String,
+}
+
+/// Serializes bibliography entries to a Hayagriva YAML file, which can be
+/// loaded by Typst's `bibliography`
+///
+/// Since the entries are free-form text, each of them becomes a `misc` entry
+/// titled with the whole text.
+///
+/// ```
+/// use mitex::{hayagriva_yaml, BibItem};
+///
+/// let items = [BibItem {
+///     key: "knuth84".into(),
+///     label: None,
+///     text: "D. Knuth, The TeXbook, 1984.".into(),
+/// }];
+/// assert_eq!(
+///     hayagriva_yaml(&items),
+///     "\"knuth84\":\n  type: misc\n  title: \"D. Knuth, The TeXbook, 1984.\"\n"
+/// );
+/// ```
+pub fn hayagriva_yaml(items: &[BibItem]) -> String {
+    let mut yaml = String::new();
+    for item in items {
+        write_yaml_str(&mut yaml, &item.key);
+        yaml.push_str(":\n  type: misc\n  title: ");
+        write_yaml_str(&mut yaml, &item.text);
+        yaml.push('\n');
+    }
+    yaml
+}
+
+/// Writes a double-quoted YAML string
+fn write_yaml_str(yaml: &mut String, s: &str) {
+    yaml.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => yaml.push_str("\\\""),
+            '\\' => yaml.push_str("\\\\"),
+            '\n' => yaml.push_str("\\n"),
+            '\t' => yaml.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(yaml, "\\u{:04x}", c as u32);
+            }
+            c => yaml.push(c),
+        }
+    }
+    yaml.push('"');
+}
@@ -1,7 +1,6 @@
 use core::fmt;
-use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Write;
-use std::rc::Rc;
 
 pub use mitex_parser::spec::*;
 
@@ -12,7 +11,20 @@ use mitex_parser::syntax::SyntaxNode;
 use mitex_spec_gen::DEFAULT_SPEC;
 use rowan::ast::AstNode;
 
-#[derive(Debug, Clone, Copy, Default)]
+use crate::bibliography::BibItem;
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+use crate::document::{DocumentMetadata, DocumentTemplate};
+use crate::handler::{
+    Align, ConvertContext, EnvHandler, Figure, Handlers, List, Tabular, Theorem, TheoremEnv,
+    Verbatim,
+};
+use crate::markup;
+use crate::options::{ConvertOptions, MathStyle, UnknownCommandPolicy};
+use crate::source_map::SourceMapping;
+use crate::typography;
+
+/// The mode of LaTeX code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum LaTeXMode {
     #[default]
     Text,
@@ -20,7 +32,7 @@ pub enum LaTeXMode {
 }
 
 #[derive(Debug, Clone, Copy, Default)]
-enum LaTeXEnv {
+pub(crate) enum LaTeXEnv {
     #[default]
     // Text mode
     None,
@@ -28,6 +40,8 @@ enum LaTeXEnv {
     Table,
     Itemize,
     Enumerate,
+    Description,
+    Theorem,
     // Math mode
     Math,
     Matrix,
@@ -36,51 +50,112 @@ enum LaTeXEnv {
     MathCurlyGroup,
 }
 
+impl LaTeXEnv {
+    /// Whether the environment is a list, whose items are indented
+    pub(crate) fn is_list(self) -> bool {
+        matches!(self, Self::Itemize | Self::Enumerate | Self::Description)
+    }
+}
+
 pub struct Converter {
-    mode: LaTeXMode,
-    env: LaTeXEnv,
-    // indent for itemize and enumerate
-    indent: usize,
+    pub(crate) mode: LaTeXMode,
+    pub(crate) env: LaTeXEnv,
+    // indent for itemize, enumerate and description
+    pub(crate) indent: usize,
     // label for block equation
-    label: Option<String>,
+    pub(crate) label: Option<String>,
     // skip the space at the beginning of the line
     skip_next_space: bool,
+    // whether to write labels
+    pub(crate) emit_labels: bool,
+    // handlers for commands and environments
+    handlers: Handlers,
+    // what to do with unknown commands
+    unknown_command: UnknownCommandPolicy,
+    // whether to continue converting after an error
+    recover: bool,
+    // diagnostics collected in the recovering mode
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    // helpers in mitex-scope referred by the output, e.g. `mitex-error`
+    pub(crate) helpers: BTreeSet<&'static str>,
+    // the depth of the top level heading, computed on the first heading
+    pub(crate) heading_top: Option<i8>,
+    // whether the numbering of headings is set, before the first heading or by `\appendix`
+    pub(crate) heading_numbering: bool,
+    // range of the `\label` already attached to the previous heading
+    pub(crate) attached_label: Option<rowan::TextRange>,
+    // entries of `thebibliography` environments
+    pub(crate) bib_items: Vec<BibItem>,
+    // colors defined by `\definecolor`, in Typst
+    pub(crate) colors: HashMap<String, String>,
+    // theorem-like environments declared by `\newtheorem`
+    pub(crate) theorems: HashMap<String, Theorem>,
+    // the style of amsthm set by `\theoremstyle` for the following declarations
+    pub(crate) theorem_style: String,
+    // the font size set by declarations like `\large`, relative to the base size
+    pub(crate) font_size: f64,
+    // the metadata collected from `\documentclass`, `\title` etc.
+    pub(crate) metadata: DocumentMetadata,
+    // the template of the document class, applied in the document mode
+    pub(crate) template: Option<DocumentTemplate>,
+    // the Hayagriva file `thebibliography` is converted to load
+    pub(crate) bibliography_file: String,
+    // mappings from the output to the input, if requested
+    source_map: Option<Vec<SourceMapping>>,
 }
 
 impl Converter {
-    fn new(mode: LaTeXMode) -> Self {
+    fn new(opts: &ConvertOptions) -> Self {
         Self {
-            mode,
+            mode: opts.mode,
             env: LaTeXEnv::default(),
             indent: 0,
             label: None,
             skip_next_space: true,
+            emit_labels: opts.emit_labels,
+            handlers: opts.handlers.clone(),
+            unknown_command: opts.unknown_command,
+            recover: opts.recover,
+            diagnostics: vec![],
+            helpers: BTreeSet::new(),
+            heading_top: None,
+            heading_numbering: false,
+            attached_label: None,
+            bib_items: vec![],
+            colors: HashMap::new(),
+            theorems: HashMap::new(),
+            theorem_style: "plain".to_owned(),
+            font_size: 1.0,
+            metadata: DocumentMetadata::default(),
+            template: None,
+            bibliography_file: opts.bibliography_file.clone(),
+            source_map: opts.source_map.then(Vec::new),
         }
     }
 
     #[must_use]
-    fn enter_mode(&mut self, context: LaTeXMode) -> LaTeXMode {
+    pub(crate) fn enter_mode(&mut self, context: LaTeXMode) -> LaTeXMode {
         let prev = self.mode;
         self.mode = context;
         prev
     }
 
-    fn exit_mode(&mut self, prev: LaTeXMode) {
+    pub(crate) fn exit_mode(&mut self, prev: LaTeXMode) {
         self.mode = prev;
     }
 
     #[must_use]
-    fn enter_env(&mut self, context: LaTeXEnv) -> LaTeXEnv {
+    pub(crate) fn enter_env(&mut self, context: LaTeXEnv) -> LaTeXEnv {
         let prev = self.env;
         self.env = context;
-        if matches!(self.env, LaTeXEnv::Itemize | LaTeXEnv::Enumerate) {
+        if self.env.is_list() {
             self.indent += 2;
         }
         prev
     }
 
-    fn exit_env(&mut self, prev: LaTeXEnv) {
-        if matches!(self.env, LaTeXEnv::Itemize | LaTeXEnv::Enumerate) {
+    pub(crate) fn exit_env(&mut self, prev: LaTeXEnv) {
+        if self.env.is_list() {
             self.indent -= 2;
         }
         self.env = prev;
@@ -91,21 +166,24 @@ impl Converter {
 //     rowan::GreenNode::new(LatexSyntaxKind::TEXT.into(), [])
 // }
 
-use mitex_parser::syntax::SyntaxElement as LatexSyntaxElem;
-use mitex_parser::syntax::SyntaxKind as LatexSyntaxKind;
+pub(crate) use mitex_parser::syntax::SyntaxElement as LatexSyntaxElem;
+pub(crate) use mitex_parser::syntax::SyntaxKind as LatexSyntaxKind;
 // use mitex_parser::syntax::SyntaxNode as LatexSyntaxNode;
 
+/// An error stopping the conversion
 #[derive(Debug)]
-enum ConvertError {
+pub enum ConvertError {
+    /// Failed to write the output
     Fmt(fmt::Error),
-    Str(String),
+    /// The input cannot be converted
+    Diag(Diagnostic),
 }
 
 impl fmt::Display for ConvertError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Fmt(e) => write!(f, "fmt: {}", e),
-            Self::Str(e) => write!(f, "error: {}", e),
+            Self::Diag(e) => write!(f, "{}", e),
         }
     }
 }
@@ -116,16 +194,88 @@ impl From<fmt::Error> for ConvertError {
     }
 }
 
-impl From<String> for ConvertError {
-    fn from(e: String) -> Self {
-        Self::Str(e)
+impl From<Diagnostic> for ConvertError {
+    fn from(e: Diagnostic) -> Self {
+        Self::Diag(e)
+    }
+}
+
+/// A Typst string literal of the text
+pub(crate) fn str_literal(s: &str) -> String {
+    let mut literal = String::new();
+    // writing to a string never fails
+    let _ = write_str_literal(&mut literal, s);
+    literal
+}
+
+/// Writes a Typst string literal
+pub(crate) fn write_str_literal(f: &mut String, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            _ => f.write_char(ch)?,
+        }
     }
+    f.write_char('"')
+}
+
+/// Creates an error diagnostic spanning the given element
+pub(crate) fn error_at(
+    code: DiagnosticCode,
+    message: String,
+    elem: &LatexSyntaxElem,
+) -> ConvertError {
+    let range = elem.text_range();
+    Diagnostic::error(code, message, range.start().into()..range.end().into()).into()
 }
 
 impl Converter {
-    fn convert(
+    pub(crate) fn convert(
+        &mut self,
+        f: &mut String,
+        elem: LatexSyntaxElem,
+        spec: &CommandSpec,
+    ) -> Result<(), ConvertError> {
+        let start = f.len();
+        let input = elem.text_range();
+        let res = match self.convert_elem(f, elem, spec) {
+            Err(ConvertError::Diag(diag)) if self.recover => self.convert_error(f, diag),
+            res => res,
+        };
+        // record the mapping of each element having output
+        if let Some(source_map) = &mut self.source_map {
+            if f.len() > start {
+                source_map.push(SourceMapping {
+                    output: start..f.len(),
+                    input: input.start().into()..input.end().into(),
+                });
+            }
+        }
+        res
+    }
+
+    /// Write a placeholder like `#mitex-error("unknown command: \\foo")` for an
+    /// element failed to convert, and continue converting
+    fn convert_error(&mut self, f: &mut String, diag: Diagnostic) -> Result<(), ConvertError> {
+        self.helpers.insert("mitex-error");
+        f.write_str("#mitex-error(")?;
+        write_str_literal(f, &diag.message)?;
+        f.write_char(')')?;
+        if matches!(self.mode, LaTeXMode::Text) {
+            f.write_char(';')?;
+        }
+        self.diagnostics.push(diag);
+        Ok(())
+    }
+
+    fn convert_elem(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -138,10 +288,15 @@ impl Converter {
             }
         }
         match elem.kind() {
-            TokenError => Err(match elem {
-                LatexSyntaxElem::Node(node) => format!("error unexpected: {:?}", node.text()),
-                LatexSyntaxElem::Token(token) => format!("error unexpected: {:?}", token.text()),
-            })?,
+            TokenError => {
+                let message = match &elem {
+                    LatexSyntaxElem::Node(node) => format!("error unexpected: {:?}", node.text()),
+                    LatexSyntaxElem::Token(token) => {
+                        format!("error unexpected: {:?}", token.text())
+                    }
+                };
+                Err(error_at(DiagnosticCode::UnexpectedToken, message, &elem))?
+            }
             ItemLR | ClauseArgument | ScopeRoot | ItemText | ItemBracket | ItemParen => {
                 for child in elem.as_node().unwrap().children_with_tokens() {
                     self.convert(f, child, spec)?;
@@ -159,8 +314,16 @@ impl Converter {
             ItemAttachComponent => {
                 self.convert_attach_component(f, elem, spec)?;
             }
-            ClauseCommandName => Err("command name outside of command".to_owned())?,
-            ItemBegin | ItemEnd => Err("clauses outside of environment".to_owned())?,
+            ClauseCommandName => Err(error_at(
+                DiagnosticCode::MisplacedClause,
+                "command name outside of command".to_owned(),
+                &elem,
+            ))?,
+            ItemBegin | ItemEnd => Err(error_at(
+                DiagnosticCode::MisplacedClause,
+                "clauses outside of environment".to_owned(),
+                &elem,
+            ))?,
             TokenWord => {
                 if matches!(self.mode, LaTeXMode::Math) {
                     // break up words into individual characters and add a space
@@ -170,16 +333,25 @@ impl Converter {
                         f.write_char(' ')?;
                     }
                 } else {
-                    // write the word directly in text mode
-                    f.write_str(elem.as_token().unwrap().text())?;
+                    // write the word with the ligatures like `--` replaced,
+                    // escaping the markup syntax
+                    let mut word = String::new();
+                    typography::write_word(&mut word, elem.as_token().unwrap().text());
+                    markup::write_word(f, &word);
                 }
             }
+            // raw text with its delimiters, if not converted by the handler
+            TokenVerbatim => {
+                f.write_str("#raw(")?;
+                write_str_literal(f, elem.as_token().unwrap().text())?;
+                f.write_char(')')?;
+            }
             // do nothing
             TokenLBrace | TokenRBrace | TokenDollar | TokenBeginMath | TokenEndMath
             | TokenComment | ItemBlockComment => {}
             // space identical
             TokenWhiteSpace => {
-                // indent for itemize and enumerate
+                // indent for lists
                 if self.skip_next_space {
                     self.skip_next_space = false;
                     return Ok(());
@@ -188,7 +360,7 @@ impl Converter {
             }
             TokenLineBreak => {
                 write!(f, "{}", elem.as_token().unwrap().text())?;
-                // indent for itemize and enumerate
+                // indent for lists
                 for _ in 0..self.indent {
                     f.write_char(' ')?;
                 }
@@ -196,7 +368,11 @@ impl Converter {
             }
             // escapes
             TokenApostrophe => {
-                f.write_char('\'')?;
+                if matches!(self.mode, LaTeXMode::Math) {
+                    f.write_char('\'')?;
+                } else {
+                    f.write_str(typography::apostrophe_quote(elem.as_token().unwrap()))?;
+                }
             }
             TokenComma => {
                 f.write_str("\\,")?;
@@ -241,7 +417,7 @@ impl Converter {
                 if matches!(self.mode, LaTeXMode::Math) {
                     f.write_str("space.nobreak ")?;
                 } else {
-                    f.write_str("\\~")?;
+                    f.write_char('~')?;
                 }
             }
             TokenAmpersand => match self.env {
@@ -264,18 +440,12 @@ impl Converter {
                 // remove prefix \
                 let name = &name[1..];
 
-                match name {
-                    "item" => {
-                        self.convert_command_item(f)?;
-                    }
-                    "label" => {
-                        self.convert_command_label(f, &cmd)?;
+                match self.handlers.command(name) {
+                    Some(handler) => {
+                        handler.convert(&mut ConvertContext::new(self, spec), f, &cmd)?;
                     }
-                    "includegraphics" => {
-                        self.convert_command_includegraphics(f, &cmd)?;
-                    }
-                    _ => {
-                        self.convert_normal_command(f, elem, spec)?;
+                    None => {
+                        self.convert_normal_command(f, elem, spec, None)?;
                     }
                 }
             }
@@ -293,13 +463,17 @@ impl Converter {
     /// Convert formula like `$x$` or `$$x$$`
     fn convert_formula(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
         let formula = FormulaItem::cast(elem.as_node().unwrap().clone()).unwrap();
         if !formula.is_valid() {
-            Err("formula is not valid".to_owned())?
+            Err(error_at(
+                DiagnosticCode::InvalidFormula,
+                "formula is not valid".to_owned(),
+                &elem,
+            ))?
         }
         if matches!(self.mode, LaTeXMode::Text) {
             if formula.is_inline() {
@@ -326,7 +500,7 @@ impl Converter {
     /// Convert curly group like `{abc}`
     fn convert_curly_group(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -365,7 +539,7 @@ impl Converter {
     /// Convert \left and \right
     fn convert_clause_lr(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -403,7 +577,7 @@ impl Converter {
     /// Convert attach component like `x_1^2`
     fn convert_attach_component(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -447,7 +621,7 @@ impl Converter {
     /// Convert command symbol like `\alpha`
     fn convert_command_sym(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -455,9 +629,9 @@ impl Converter {
         // remove prefix \
         let name = &name[1..];
         // get cmd_shape and arg_shape from spec
-        let cmd_shape = spec
-            .get_cmd(name)
-            .ok_or_else(|| format!("unknown command: \\{}", name))?;
+        let Some(cmd_shape) = spec.get_cmd(name) else {
+            return self.convert_unknown_command(f, name, &elem);
+        };
         // typst alias name
         let typst_name = cmd_shape.alias.as_deref().unwrap_or(name);
         // write to output
@@ -465,133 +639,42 @@ impl Converter {
         Ok(())
     }
 
-    /// Convert command `\item` for itemize and enumerate
-    fn convert_command_item(&mut self, f: &mut fmt::Formatter<'_>) -> Result<(), ConvertError> {
-        if matches!(self.env, LaTeXEnv::Itemize | LaTeXEnv::Enumerate) {
-            f.write_char('\n')?;
-            for _ in 0..(self.indent - 2) {
-                f.write_char(' ')?;
-            }
-            if matches!(self.env, LaTeXEnv::Itemize) {
-                f.write_str("- ")?;
-            } else {
-                f.write_str("+ ")?;
-            }
-        } else {
-            Err("item command outside of itemize or enumerate".to_owned())?;
-        }
-        Ok(())
-    }
-
-    /// Convert command `\label`
-    fn convert_command_label(
+    /// Convert command not found in the spec according to the policy
+    fn convert_unknown_command(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
-        cmd: &CmdItem,
+        f: &mut String,
+        name: &str,
+        elem: &LatexSyntaxElem,
     ) -> Result<(), ConvertError> {
-        let arg = cmd
-            .arguments()
-            .next()
-            .expect("\\label command must have one argument");
-        // remove { and } then trim
-        let label = arg.text().to_string();
-        let label = &label[1..(label.len() - 1)];
-        let label = label.trim();
-        match self.env {
-            LaTeXEnv::None | LaTeXEnv::Itemize | LaTeXEnv::Enumerate => {
-                if matches!(self.mode, LaTeXMode::Text) {
-                    f.write_char('<')?;
-                    f.write_str(label)?;
-                    f.write_char('>')?;
-                }
+        let message = format!("unknown command: \\{name}");
+        let range = elem.text_range();
+        let range = range.start().into()..range.end().into();
+        match self.unknown_command {
+            UnknownCommandPolicy::Error => {
+                return Err(
+                    Diagnostic::error(DiagnosticCode::UnknownCommand, message, range).into(),
+                );
             }
-            _ => {
-                self.label = Some(label.to_string());
-            }
-        }
-        Ok(())
-    }
-
-    /// Convert command `\includegraphics[width=0.5\textwidth]{example-image}`
-    fn convert_command_includegraphics(
-        &mut self,
-        f: &mut fmt::Formatter<'_>,
-        cmd: &CmdItem,
-    ) -> Result<(), ConvertError> {
-        let opt_arg = cmd.arguments().find(|arg| {
-            matches!(
-                arg.first_child().unwrap().kind(),
-                LatexSyntaxKind::ItemBracket
-            )
-        });
-        let arg = cmd
-            .arguments()
-            .find(|arg| {
-                matches!(
-                    arg.first_child().unwrap().kind(),
-                    LatexSyntaxKind::ItemCurly
-                )
-            })
-            .expect("\\includegraphics command must have one argument");
-        // remove { and } then trim
-        let body = arg.text().to_string();
-        let body = &body[1..(body.len() - 1)];
-        let body = body.trim();
-        f.write_str("#image(")?;
-        // optional arguments
-        if let Some(opt_arg) = opt_arg {
-            let arg_text = opt_arg.text().to_string();
-            let arg_text = &arg_text[1..(arg_text.len() - 1)];
-            let arg_text = arg_text.trim();
-            // example: \includegraphics[width=0.5\textwidth, height=3cm,
-            // angle=45]{example-image} split by comma and convert
-            // to key-value pairs
-            let args = arg_text.split(',').collect::<Vec<_>>();
-            let args = args
-                .iter()
-                .map(|arg| {
-                    let arg = arg.trim();
-                    let arg = arg.split('=').collect::<Vec<_>>();
-                    let key = arg[0].trim();
-                    let value = if arg.len() == 2 { arg[1].trim() } else { "" };
-                    (key, value)
-                })
-                .collect::<Vec<_>>();
-            for (key, value) in args.iter() {
-                if matches!(key, &"width" | &"height") {
-                    f.write_str(key)?;
-                    f.write_char(':')?;
-                    f.write_char(' ')?;
-                    if value.ends_with("\\textwidth") {
-                        let value = value.trim_end_matches("\\textwidth");
-                        f.write_str(value)?;
-                        f.write_str(" * 100%")?;
-                    } else if value.ends_with("\\textheight") {
-                        let value = value.trim_end_matches("\\textheight");
-                        f.write_str(value)?;
-                        f.write_str(" * 100%")?;
-                    } else {
-                        f.write_str(value)?;
-                    }
-                    f.write_char(',')?;
+            UnknownCommandPolicy::Keep => {
+                f.write_str(name)?;
+                if matches!(self.mode, LaTeXMode::Math) {
                     f.write_char(' ')?;
                 }
             }
+            UnknownCommandPolicy::Ignore => {}
         }
-        // image path
-        f.write_char('"')?;
-        f.write_str(body)?;
-        f.write_char('"')?;
-        f.write_char(')')?;
+        let diag = Diagnostic::warning(DiagnosticCode::UnknownCommand, message, range);
+        self.diagnostics.push(diag);
         Ok(())
     }
 
-    /// Convert normal command
-    fn convert_normal_command(
+    /// Convert normal command, written as `alias` if given
+    pub(crate) fn convert_normal_command(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
+        alias: Option<&str>,
     ) -> Result<(), ConvertError> {
         let cmd = CmdItem::cast(elem.as_node().unwrap().clone()).unwrap();
         let name = cmd.name_tok().unwrap();
@@ -606,22 +689,13 @@ impl Converter {
             .collect::<Vec<_>>();
 
         // get cmd_shape and arg_shape from spec
-        let cmd_shape = spec
-            .get_cmd(name)
-            .ok_or_else(|| format!("unknown command: \\{}", name))?;
+        let Some(cmd_shape) = spec.get_cmd(name) else {
+            return self.convert_unknown_command(f, name, &elem);
+        };
         let arg_shape = &cmd_shape.args;
 
         // typst alias name
-        let mut typst_name = cmd_shape.alias.as_deref().unwrap_or(name);
-
-        // hack for textbf and textit
-        if matches!(self.mode, LaTeXMode::Text) {
-            if name == "textbf" {
-                typst_name = "#strong";
-            } else if name == "textit" {
-                typst_name = "#emph";
-            }
-        }
+        let typst_name = alias.unwrap_or(cmd_shape.alias.as_deref().unwrap_or(name));
 
         // normal command
         write!(f, "{}", typst_name)?;
@@ -703,7 +777,7 @@ impl Converter {
     /// Convert environments
     fn convert_env(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
+        f: &mut String,
         elem: LatexSyntaxElem,
         spec: &CommandSpec,
     ) -> Result<(), ConvertError> {
@@ -716,41 +790,55 @@ impl Converter {
         let name = name.trim();
         let args = env.arguments();
 
-        let env_shape = spec
-            .get_env(name)
-            .ok_or_else(|| format!("unknown environment: \\{}", name))?;
+        if let Some(handler) = self.handlers.env(name) {
+            handler.convert(&mut ConvertContext::new(self, spec), f, &env)?;
+            // the space after `\end` is kept even if the body ends with a
+            // line break
+            self.skip_next_space = false;
+            return self.convert_env_label(f);
+        }
+        // declared by `\newtheorem`, which takes the label in its body
+        if self.theorems.contains_key(name) {
+            TheoremEnv.convert(&mut ConvertContext::new(self, spec), f, &env)?;
+            self.skip_next_space = false;
+            return Ok(());
+        }
+
+        let env_shape = spec.get_env(name).ok_or_else(|| {
+            error_at(
+                DiagnosticCode::UnknownEnvironment,
+                format!("unknown environment: \\{name}"),
+                &elem,
+            )
+        })?;
         let typst_name = env_shape.alias.as_deref().unwrap_or(name);
 
         let env_kind = match env_shape.ctx_feature {
             ContextFeature::None => LaTeXEnv::None,
             ContextFeature::IsMath => LaTeXEnv::Math,
+            // numbered row by row in text mode, and like math environments
+            // in math mode
+            ContextFeature::IsAlign => {
+                if matches!(self.mode, LaTeXMode::Text) {
+                    return Align.convert(&mut ConvertContext::new(self, spec), f, &env);
+                }
+                LaTeXEnv::Math
+            }
+            // raw text in both modes
+            ContextFeature::IsVerbatim => {
+                return Verbatim.convert(&mut ConvertContext::new(self, spec), f, &env);
+            }
             ContextFeature::IsMatrix => LaTeXEnv::Matrix,
             ContextFeature::IsCases => LaTeXEnv::Cases,
             ContextFeature::IsFigure => LaTeXEnv::Figure,
             ContextFeature::IsTable => LaTeXEnv::Table,
-            ContextFeature::IsItemize => LaTeXEnv::Itemize,
-            ContextFeature::IsEnumerate => LaTeXEnv::Enumerate,
-        };
-
-        // hack for itemize and enumerate
-        if matches!(env_kind, LaTeXEnv::Itemize | LaTeXEnv::Enumerate) {
-            let prev = self.enter_env(env_kind);
-
-            for child in elem.as_node().unwrap().children_with_tokens() {
-                if matches!(
-                    child.kind(),
-                    LatexSyntaxKind::ItemBegin | LatexSyntaxKind::ItemEnd
-                ) {
-                    continue;
-                }
-
-                self.convert(f, child, spec)?;
+            // markup lists, whose items are converted by the handler
+            ContextFeature::IsItemize
+            | ContextFeature::IsEnumerate
+            | ContextFeature::IsDescription => {
+                return List.convert(&mut ConvertContext::new(self, spec), f, &env);
             }
-
-            self.exit_env(prev);
-
-            return Ok(());
-        }
+        };
 
         // is environment for math
         let is_math_env = matches!(
@@ -809,14 +897,15 @@ impl Converter {
             //    \begin{abstract}xxx\end{abstract} -> #quote(block: true)[xxx]
             // 2. \begin{figure}xxx\end{figure} -> #figure(image(), caption: [])
             //    \begin{table}xxx\end{table} -> #figure(table(), caption: [])
-            // 3. \begin{tabular}xxx\end{tabular}
+            // 3. \begin{tabular}xxx\end{tabular}, and tabular*, tabularx and
+            //    longtable -> #table()
             // environment name
             match env_kind {
                 LaTeXEnv::Figure => {
-                    self.convert_env_figure(f, elem, spec, env_kind, typst_name)?;
+                    Figure.convert(&mut ConvertContext::new(self, spec), f, &env)?;
                 }
                 LaTeXEnv::Table => {
-                    self.convert_env_table(f, elem, spec, env_kind, typst_name)?;
+                    Tabular.convert(&mut ConvertContext::new(self, spec), f, &env)?;
                 }
                 _ => {
                     // normal environment
@@ -840,266 +929,215 @@ impl Converter {
             }
         }
 
-        // handle label, only add <label> for text mode
-        if matches!(self.mode, LaTeXMode::Text) {
-            if let Some(label) = self.label.take() {
-                f.write_char('<')?;
-                f.write_str(label.as_str())?;
-                f.write_char('>')?;
-                self.label = None;
-            }
-        }
-
-        Ok(())
+        self.convert_env_label(f)
     }
 
-    /// Convert figure environment
-    fn convert_env_figure(
+    /// Converts the body of the `document` environment in the document mode,
+    /// only reading the commands with handlers in the preamble, e.g.
+    /// `\title`, and dropping anything else outside the environment
+    ///
+    /// The template of the class given by `\documentclass` is picked from the
+    /// `templates` after reading the preamble.
+    fn convert_document(
         &mut self,
-        f: &mut fmt::Formatter<'_>,
-        elem: LatexSyntaxElem,
+        f: &mut String,
+        root: SyntaxNode,
         spec: &CommandSpec,
-        env_kind: LaTeXEnv,
-        typst_name: &str,
+        templates: &HashMap<String, DocumentTemplate>,
     ) -> Result<(), ConvertError> {
-        fn is_named_arg(child: &LatexSyntaxElem) -> bool {
-            matches!(
-                child.kind(),
-                LatexSyntaxKind::ItemCmd
-                    if matches!(
-                        CmdItem::cast(child.as_node().unwrap().clone())
-                            .unwrap()
-                            .name_tok()
-                            .unwrap()
-                            .text(),
-                        "\\caption" | "\\centering"
-                    )
-            )
-        }
-        // collect named args
-        let mut caption = None;
-        for child in elem.as_node().unwrap().children_with_tokens() {
-            if is_named_arg(&child) {
-                let cmd = CmdItem::cast(child.as_node().unwrap().clone()).unwrap();
-                let name = cmd.name_tok().unwrap();
-                let name = name.text();
-                // remove prefix \
-                let name = &name[1..];
-                if name == "caption" {
-                    let arg = cmd
-                        .arguments()
-                        .next()
-                        .expect("\\caption command must have one argument");
-                    caption = Some(arg);
-                }
+        let is_document = |env: &EnvItem| {
+            env.name_tok()
+                .is_some_and(|name| name.text().trim() == "document")
+        };
+        let Some(document) = root.children().filter_map(EnvItem::cast).find(is_document) else {
+            return self.convert(f, LatexSyntaxElem::Node(root), spec);
+        };
+
+        let preamble = root
+            .children()
+            .take_while(|node| node != document.syntax())
+            .filter_map(CmdItem::cast);
+        for cmd in preamble {
+            let name = cmd.name_tok().unwrap();
+            let Some(handler) = self.handlers.command(&name.text()[1..]) else {
+                continue;
+            };
+            let res = handler.convert(
+                &mut ConvertContext::new(self, spec),
+                &mut String::new(),
+                &cmd,
+            );
+            match res {
+                Err(ConvertError::Diag(diag)) if self.recover => self.diagnostics.push(diag),
+                res => res?,
             }
         }
-        // convert to #figure
-        let prev = self.enter_env(env_kind);
-        f.write_char('#')?;
-        f.write_str(typst_name)?;
-        f.write_char('(')?;
-        if let Some(caption) = caption {
-            f.write_str("caption: [")?;
-            self.convert(f, caption.into(), spec)?;
-            f.write_str("],")?;
+        let class = self.metadata.class.as_ref();
+        self.template = class.and_then(|class| templates.get(class)).cloned();
+        if let Some(helper) = self.template.as_ref().and_then(DocumentTemplate::helper) {
+            self.helpers.insert(helper);
         }
-        f.write_str(")[")?;
-        for child in elem.as_node().unwrap().children_with_tokens() {
-            // skip \begin and \end commands
+
+        for child in document.syntax().children_with_tokens() {
             if matches!(
                 child.kind(),
                 LatexSyntaxKind::ItemBegin | LatexSyntaxKind::ItemEnd
-            ) || matches!(child.kind(), LatexSyntaxKind::ItemCmd) && is_named_arg(&child)
-            {
+            ) {
                 continue;
             }
             self.convert(f, child, spec)?;
         }
-        f.write_str("];")?;
-        self.exit_env(prev);
-
         Ok(())
     }
 
-    /// Convert tabular environment
-    fn convert_env_table(
-        &mut self,
-        f: &mut fmt::Formatter<'_>,
-        elem: LatexSyntaxElem,
-        spec: &CommandSpec,
-        env_kind: LaTeXEnv,
-        typst_name: &str,
-    ) -> Result<(), ConvertError> {
-        let env = EnvItem::cast(elem.as_node().unwrap().clone()).unwrap();
-        let arg = env
-            .arguments()
-            .next()
-            .expect("tabular environment must have one argument");
-        let arg = arg.text().to_string();
-        // remove { and } then trim
-        let arg = &arg[1..(arg.len() - 1)];
-        let arg = arg.trim();
-        // split arg to get alignments and vertical lines
-        let mut alignments: Vec<char> = vec![];
-        let mut vlines: Vec<usize> = vec![];
-        let mut index = 0;
-        for ch in arg.chars() {
-            match ch {
-                'l' | 'c' | 'r' => {
-                    alignments.push(ch);
-                    index += 1;
-                }
-                '|' => {
-                    vlines.push(index);
-                }
-                _ => {
-                    // unknown character
-                    Err(format!("unknown alignment: {}", ch))?;
-                }
-            }
-        }
-        // convert to #figure
-        let prev = self.enter_env(env_kind);
-        f.write_char('#')?;
-        f.write_str(typst_name)?;
-        f.write_char('(')?;
-        // stroke: none,
-        f.write_str("stroke: none,\n")?;
-        // columns: 2,
-        f.write_str(format!("columns: {},\n", alignments.len()).as_str())?;
-        // align: (left, center, right, ),
-        f.write_str("align: (")?;
-        for align in alignments {
-            match align {
-                'l' => f.write_str("left, ")?,
-                'c' => f.write_str("center, ")?,
-                'r' => f.write_str("right, ")?,
-                _ => {}
-            }
-        }
-        f.write_str("),\n")?;
-        // table.vline(x: 1),
-        for vline in vlines {
-            f.write_str(format!("table.vline(stroke: .5pt, x: {}), ", vline).as_str())?;
-        }
-        f.write_str("\n")?;
-        let mut exterior = true;
-        for child in elem.as_node().unwrap().children_with_tokens() {
-            // skip \begin and \end commands
-            if matches!(
-                child.kind(),
-                LatexSyntaxKind::ItemBegin | LatexSyntaxKind::ItemEnd
-            ) {
-                continue;
-            }
-            // preprocess if exterior
-            if exterior {
-                match child.kind() {
-                    LatexSyntaxKind::TokenWhiteSpace | LatexSyntaxKind::TokenLineBreak => {}
-                    LatexSyntaxKind::TokenAmpersand => {
-                        // write `[],` for empty exterior when encountering &
-                        f.write_str("[], ")?;
-                    }
-                    LatexSyntaxKind::ItemNewLine => {
-                        // write `[],\n` for empty exterior when encountering //
-                        f.write_str("[],\n")?;
-                    }
-                    LatexSyntaxKind::ItemCmd => {
-                        let cmd_name = CmdItem::cast(child.as_node().unwrap().clone())
-                            .unwrap()
-                            .name_tok()
-                            .unwrap();
-                        let cmd_name = &cmd_name.text()[1..];
-                        match cmd_name {
-                            "hline" => {
-                                f.write_str("table.hline(stroke: .5pt),\n")?;
-                            }
-                            "toprule" => {
-                                f.write_str("table.hline(stroke: 1pt),\n")?;
-                            }
-                            "midrule" => {
-                                f.write_str("table.hline(stroke: .5pt),\n")?;
-                            }
-                            "bottomrule" => {
-                                f.write_str("table.hline(stroke: 1pt),\n")?;
-                            }
-                            _ => {
-                                // enter interior for normal commands
-                                exterior = false;
-                                f.write_char('[')?;
-                            }
-                        }
-                    }
-                    _ => {
-                        // enter interior for normal text
-                        exterior = false;
-                        f.write_char('[')?;
-                    }
-                }
-            }
-            // process if interior
-            if !exterior {
-                match child.kind() {
-                    LatexSyntaxKind::TokenAmpersand => {
-                        // write `[],` when encountering &
-                        f.write_str("], ")?;
-                        exterior = true;
-                    }
-                    LatexSyntaxKind::ItemNewLine => {
-                        // write `[],\n` when encountering //
-                        f.write_str("],\n")?;
-                        exterior = true;
-                    }
-                    _ => {
-                        self.convert(f, child, spec)?;
-                    }
-                }
+    /// Write the label of the environment just converted
+    fn convert_env_label(&mut self, f: &mut String) -> Result<(), ConvertError> {
+        // handle label, only add <label> for text mode
+        if matches!(self.mode, LaTeXMode::Text) && self.emit_labels {
+            if let Some(label) = self.label.take() {
+                f.write_char('<')?;
+                f.write_str(label.as_str())?;
+                f.write_char('>')?;
+                self.label = None;
             }
         }
-        f.write_str(");")?;
-        self.exit_env(prev);
+
         Ok(())
     }
 }
 
-struct TypstRepr {
-    elem: LatexSyntaxElem,
-    mode: LaTeXMode,
-    spec: CommandSpec,
-    error: Rc<RefCell<String>>,
+/// The converted Typst code along with the diagnostics reported during
+/// conversion
+#[derive(Debug, Clone, Default)]
+pub struct Converted {
+    /// The converted Typst code
+    ///
+    /// If the conversion stops at an error, it holds the code converted
+    /// before the error. In the recovering mode, it holds the whole code with
+    /// placeholders for the elements failed to convert.
+    pub output: String,
+    /// The diagnostics in the order they are reported
+    pub diagnostics: Vec<Diagnostic>,
+    /// The mappings from the output to the input sorted by the output
+    /// ranges, if requested
+    pub source_map: Option<Vec<SourceMapping>>,
+    /// The entries of inline `thebibliography` environments, which can be
+    /// written to a Hayagriva file by [`crate::hayagriva_yaml`]
+    pub bib_items: Vec<BibItem>,
+    /// The metadata of the document, e.g. the title and the authors
+    pub metadata: DocumentMetadata,
 }
 
-impl fmt::Display for TypstRepr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut ctx = Converter::new(self.mode);
-        if let Err(e) = ctx.convert(f, self.elem.clone(), &self.spec) {
-            self.error.borrow_mut().push_str(&e.to_string());
-            return Err(fmt::Error);
+impl Converted {
+    /// Whether any error is reported
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// Returns the output if no error is reported, otherwise the first error
+    pub fn into_result(self) -> Result<String, String> {
+        match self.diagnostics.into_iter().find(Diagnostic::is_error) {
+            Some(e) => Err(e.to_string()),
+            None => Ok(self.output),
         }
-        Ok(())
     }
 }
 
-#[inline(always)]
-pub fn convert_inner(
-    input: &str,
-    mode: LaTeXMode,
-    spec: Option<CommandSpec>,
-    do_parse: fn(input: &str, spec: CommandSpec) -> SyntaxNode,
-) -> Result<String, String> {
-    let node = do_parse(input, spec.unwrap_or_else(|| DEFAULT_SPEC.clone()));
+/// Converts LaTeX code with the given options
+pub fn convert_inner(input: &str, opts: &ConvertOptions) -> Converted {
+    let spec = opts.spec.clone().unwrap_or_else(|| DEFAULT_SPEC.clone());
+    let (node, input_map) = if opts.expand_macros {
+        mitex_parser::parse_with_input_map(input, spec.clone())
+    } else {
+        mitex_parser::parse_without_macro_with_input_map(input, spec.clone())
+    };
     // println!("{:#?}", node);
     // println!("{:#?}", node.text());
+    let mut converted = convert_node_inner(node, &spec, opts);
+    // the ranges are in the syntax tree, which drops some text of the input
+    for diag in &mut converted.diagnostics {
+        diag.range = input_map.range(diag.range.clone());
+    }
+    for mapping in converted.source_map.iter_mut().flatten() {
+        mapping.input = input_map.range(mapping.input.clone());
+    }
+    converted
+}
+
+/// Converts a parsed syntax node with the given options
+pub fn convert_node_inner(
+    node: SyntaxNode,
+    spec: &CommandSpec,
+    opts: &ConvertOptions,
+) -> Converted {
     let mut output = String::new();
-    let err = String::new();
-    let err = Rc::new(RefCell::new(err));
-    let repr = TypstRepr {
-        elem: LatexSyntaxElem::Node(node),
-        mode,
-        spec: DEFAULT_SPEC.clone(),
-        error: err.clone(),
-    };
-    core::fmt::write(&mut output, format_args!("{}", repr)).map_err(|_| err.borrow().to_owned())?;
-    Ok(output)
+    let mut ctx = Converter::new(opts);
+    let res: Result<(), ConvertError> = (|| {
+        let (open, close) = match (opts.mode, opts.math_style) {
+            (LaTeXMode::Text, _) | (_, MathStyle::Bare) => ("", ""),
+            (LaTeXMode::Math, MathStyle::Inline) => ("$", "$"),
+            (LaTeXMode::Math, MathStyle::Display) => ("$ ", " $"),
+        };
+        output.push_str(open);
+        if opts.document {
+            ctx.convert_document(&mut output, node, spec, &opts.templates)?;
+        } else {
+            ctx.convert(&mut output, LatexSyntaxElem::Node(node), spec)?;
+        }
+        output.push_str(close);
+        Ok(())
+    })();
+    let mut diagnostics = std::mem::take(&mut ctx.diagnostics);
+    if let Err(ConvertError::Diag(e)) = res {
+        diagnostics.push(e);
+    }
+    let mut source_map = ctx.source_map.take().map(|mut source_map| {
+        // outer elements come first if they start at the same position
+        source_map.sort_by_key(|m| {
+            use std::cmp::Reverse;
+            let (output, input) = (&m.output, &m.input);
+            (
+                output.start,
+                Reverse(output.end),
+                input.start,
+                Reverse(input.end),
+            )
+        });
+        // nodes wrapping a single element have the same mapping
+        source_map.dedup();
+        source_map
+    });
+    let mut header = String::new();
+    if opts.emit_prelude {
+        header = crate::prelude::typst_prelude(spec, ctx.helpers.iter().copied());
+    }
+    // `document` rules must come before any content
+    if opts.document {
+        let _ = ctx.metadata.write_set_rule(&mut header);
+        // the options of the class are passed to its template if any
+        let class_options = match &ctx.template {
+            Some(_) => &[][..],
+            None => &ctx.metadata.class_options[..],
+        };
+        let _ = ctx
+            .metadata
+            .layout
+            .write_set_rules(class_options, &mut header);
+        if let Some(template) = &ctx.template {
+            let _ = template.write_show_rule(&ctx.metadata, &mut header);
+        }
+    }
+    output.insert_str(0, &header);
+    // shift the output ranges by the prelude
+    for mapping in source_map.iter_mut().flatten() {
+        mapping.output.start += header.len();
+        mapping.output.end += header.len();
+    }
+    Converted {
+        output,
+        diagnostics,
+        source_map,
+        bib_items: ctx.bib_items,
+        metadata: ctx.metadata,
+    }
 }
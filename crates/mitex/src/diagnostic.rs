@@ -0,0 +1,121 @@
+use core::fmt;
+use std::ops::Range;
+
+/// The severity of a [`Diagnostic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The input cannot be converted
+    Error,
+    /// The input is converted, but the result may not be what is expected
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        })
+    }
+}
+
+/// A stable code identifying the kind of a [`Diagnostic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// The parser produced an error node, e.g. an unmatched `\right`
+    UnexpectedToken,
+    /// A formula is not closed, e.g. `$x`
+    InvalidFormula,
+    /// A command is not found in the command spec
+    UnknownCommand,
+    /// An environment is not found in the command spec
+    UnknownEnvironment,
+    /// A clause like `\begin{..}` appears outside of its item
+    MisplacedClause,
+    /// An `\item` command appears outside of a list environment
+    ItemOutsideList,
+    /// A command or environment lacks a required argument, e.g. `\ref`
+    MissingArgument,
+    /// A column specifier of a tabular is not recognized
+    UnknownAlignment,
+    /// A color is neither predefined nor defined by `\definecolor`
+    UnknownColor,
+    /// The input is recognized but only partially converted, e.g. `@{..}`
+    /// in a column specification
+    Unsupported,
+}
+
+impl DiagnosticCode {
+    /// Returns the stable string representation of the code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnexpectedToken => "unexpected-token",
+            Self::InvalidFormula => "invalid-formula",
+            Self::UnknownCommand => "unknown-command",
+            Self::UnknownEnvironment => "unknown-environment",
+            Self::MisplacedClause => "misplaced-clause",
+            Self::ItemOutsideList => "item-outside-list",
+            Self::MissingArgument => "missing-argument",
+            Self::UnknownAlignment => "unknown-alignment",
+            Self::UnknownColor => "unknown-color",
+            Self::Unsupported => "unsupported",
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem found in the input during conversion
+///
+/// The `range` is a byte range into the LaTeX input for the functions
+/// converting the input, like [`crate::convert`]. For [`crate::convert_node`],
+/// it is a byte range into the text of the syntax tree instead, which can be
+/// mapped to the input by [`crate::InputMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic
+    pub severity: Severity,
+    /// The kind of the diagnostic
+    pub code: DiagnosticCode,
+    /// The human-readable message
+    pub message: String,
+    /// The byte range of the input causing the diagnostic
+    pub range: Range<usize>,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic
+    pub fn error(code: DiagnosticCode, message: impl Into<String>, range: Range<usize>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            range,
+        }
+    }
+
+    /// Creates a warning diagnostic
+    pub fn warning(code: DiagnosticCode, message: impl Into<String>, range: Range<usize>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            range,
+        }
+    }
+
+    /// Whether the diagnostic is an error
+    pub fn is_error(&self) -> bool {
+        matches!(self.severity, Severity::Error)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+use crate::converter::{str_literal, write_str_literal};
+
+/// The metadata of a document, collected from `\documentclass` and the
+/// commands for the title block like `\title` and `\author`
+///
+/// The texts are converted to Typst markup unless noted otherwise.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocumentMetadata {
+    /// The class of `\documentclass`, e.g. `article`
+    pub class: Option<String>,
+    /// The options of the class, e.g. `11pt` and `twocolumn`
+    pub class_options: Vec<String>,
+    /// The title of `\title`
+    pub title: Option<String>,
+    /// The authors of `\author` separated by `\and`, or given by several
+    /// `\author`s
+    pub authors: Vec<Author>,
+    /// The date of `\date`
+    pub date: Option<String>,
+    /// The keywords of `\keywords`, as plain text
+    pub keywords: Vec<String>,
+    /// The body of the `abstract` environment
+    pub r#abstract: Option<String>,
+    /// The layout set in the preamble, e.g. by geometry
+    pub layout: Layout,
+}
+
+/// The layout of a document set by geometry, babel, `\setlength` etc.
+///
+/// The values are in Typst code, e.g. `1in` and `"de"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    /// The named arguments of `#set page(..)` other than the margins and the
+    /// numbering, e.g. `paper` to `"a4"`
+    pub page: BTreeMap<String, String>,
+    /// The margins by the sides of Typst, e.g. `left` to `1in`, where `rest`
+    /// is for the other sides
+    pub margin: BTreeMap<String, String>,
+    /// The numbering of the pages of `\pagenumbering`, e.g. `"i"`, or `none`
+    pub numbering: Option<String>,
+    /// The style of `\pagestyle`, where the pages are not numbered if it is
+    /// `empty`
+    pub page_style: Option<String>,
+    /// The named arguments of `#set par(..)`, e.g. `leading` to `0.975em`
+    pub par: BTreeMap<String, String>,
+    /// The spacing between paragraphs of `\parskip`
+    pub par_spacing: Option<String>,
+    /// The named arguments of `#set text(..)`, e.g. `lang` to `"de"`
+    pub text: BTreeMap<String, String>,
+}
+
+/// An author of a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Author {
+    /// The name, i.e. the first line of the author
+    pub name: String,
+    /// The name as plain text, e.g. for the metadata of the PDF
+    pub plain_name: String,
+    /// The lines following the name like `\\ University`, or the
+    /// `\affiliation`s following the author
+    pub affiliations: Vec<String>,
+    /// The footnotes of `\thanks` in the author
+    pub thanks: Vec<String>,
+}
+
+/// A Typst template applied to the body in the document mode, like
+/// `#show: ieee.with(title: [..], authors: (..))`
+///
+/// The template function is called with the metadata of the document as the
+/// named arguments `title`, `authors`, `date`, `abstract` and `keywords` if
+/// given, where each author is a dictionary like
+/// `(name: [..], affiliations: ([..],), thanks: ([..],))`. The options of the
+/// class are passed as `paper`, `font-size`, `columns` and `flipped`, e.g.
+/// `a4paper` as `paper: "a4"`, and the other options are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocumentTemplate {
+    /// The Typst file the function is imported from, e.g. `ieee.typ`, or none
+    /// if the function is already in scope like the built-in `mitex-article`
+    pub import: Option<String>,
+    /// The name of the template function, e.g. `ieee`
+    pub function: String,
+    /// More named arguments in Typst code, e.g. `bibliography-file` to
+    /// `"refs.bib"`, overriding the ones from the document
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub args: BTreeMap<String, String>,
+}
+
+/// The built-in templates in mitex-scope, by the document classes
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("article", "mitex-article"),
+    ("report", "mitex-report"),
+    ("IEEEtran", "mitex-ieee"),
+];
+
+impl DocumentTemplate {
+    /// Creates a template of a function in scope
+    pub fn new(function: impl Into<String>) -> Self {
+        Self {
+            function: function.into(),
+            ..Self::default()
+        }
+    }
+
+    /// The mappings from the document classes to the built-in templates
+    pub(crate) fn builtin() -> HashMap<String, Self> {
+        let templates = BUILTIN_TEMPLATES.iter();
+        templates
+            .map(|(class, function)| (class.to_string(), Self::new(*function)))
+            .collect()
+    }
+
+    /// The helper in mitex-scope if it is a built-in template
+    pub(crate) fn helper(&self) -> Option<&'static str> {
+        if self.import.is_some() {
+            return None;
+        }
+        let mut functions = BUILTIN_TEMPLATES.iter().map(|(_, function)| *function);
+        functions.find(|function| *function == self.function)
+    }
+
+    /// Writes the `#import` of the function if any, and the `#show` rule
+    /// applying it to the body with the metadata
+    pub(crate) fn write_show_rule(
+        &self,
+        metadata: &DocumentMetadata,
+        f: &mut String,
+    ) -> fmt::Result {
+        if let Some(import) = &self.import {
+            f.write_str("#import ")?;
+            write_str_literal(f, import)?;
+            writeln!(f, ": {}", self.function)?;
+        }
+
+        let mut args: Vec<(String, String)> = vec![];
+        if let Some(title) = &metadata.title {
+            args.push(("title".into(), format!("[{title}]")));
+        }
+        if !metadata.authors.is_empty() {
+            let mut authors = vec![];
+            for author in &metadata.authors {
+                let affiliations = author.affiliations.iter().map(|a| format!("[{a}]"));
+                let thanks = author.thanks.iter().map(|thanks| format!("[{thanks}]"));
+                authors.push(format!(
+                    "(name: [{}], affiliations: {}, thanks: {})",
+                    author.name,
+                    array(affiliations),
+                    array(thanks)
+                ));
+            }
+            args.push(("authors".into(), array(authors.into_iter())));
+        }
+        if let Some(date) = &metadata.date {
+            args.push(("date".into(), format!("[{date}]")));
+        }
+        if let Some(r#abstract) = &metadata.r#abstract {
+            args.push(("abstract".into(), format!("[{abstract}]")));
+        }
+        if !metadata.keywords.is_empty() {
+            args.push(("keywords".into(), str_array(&metadata.keywords)?));
+        }
+        for option in &metadata.class_options {
+            if let Some((key, value)) = class_option_arg(option) {
+                args.retain(|(k, _)| k != key);
+                args.push((key.into(), value));
+            }
+        }
+        for (key, value) in &self.args {
+            args.retain(|(k, _)| k != key);
+            args.push((key.clone(), value.clone()));
+        }
+
+        let args = args
+            .into_iter()
+            .map(|(key, value)| format!("{key}: {value}"));
+        writeln!(
+            f,
+            "#show: {}.with({})",
+            self.function,
+            args.collect::<Vec<_>>().join(", ")
+        )
+    }
+}
+
+/// Converts an option of the document class to a named argument of the
+/// template, e.g. `11pt` to `font-size: 11pt`
+pub(crate) fn class_option_arg(option: &str) -> Option<(&'static str, String)> {
+    if let Some(size) = option.strip_suffix("pt") {
+        return size
+            .parse::<f64>()
+            .ok()
+            .map(|size| ("font-size", format!("{size}pt")));
+    }
+    let paper = match option {
+        "onecolumn" => return Some(("columns", "1".into())),
+        "twocolumn" => return Some(("columns", "2".into())),
+        "landscape" => return Some(("flipped", "true".into())),
+        "a4paper" => "a4",
+        "a5paper" => "a5",
+        "b5paper" => "iso-b5",
+        "letterpaper" => "us-letter",
+        "legalpaper" => "us-legal",
+        "executivepaper" => "us-executive",
+        _ => return None,
+    };
+    Some(("paper", str_literal(paper)))
+}
+
+impl DocumentMetadata {
+    /// Writes the `#set document(..)` rule with the title, the authors and
+    /// the keywords, if any of them is given
+    pub(crate) fn write_set_rule(&self, f: &mut String) -> fmt::Result {
+        let mut args = vec![];
+        if let Some(title) = &self.title {
+            args.push(format!("title: [{title}]"));
+        }
+        if !self.authors.is_empty() {
+            let names = self.authors.iter().map(|author| author.plain_name.clone());
+            args.push(format!(
+                "author: {}",
+                str_array(&names.collect::<Vec<_>>())?
+            ));
+        }
+        if !self.keywords.is_empty() {
+            args.push(format!("keywords: {}", str_array(&self.keywords)?));
+        }
+        if !args.is_empty() {
+            writeln!(f, "#set document({})", args.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Layout {
+    /// Writes the `#set page(..)`, `#set par(..)` and `#set text(..)` rules,
+    /// if any of their arguments is given
+    ///
+    /// The `class_options` like `a4paper` are also set if they are not passed
+    /// to a template.
+    pub(crate) fn write_set_rules(&self, class_options: &[String], f: &mut String) -> fmt::Result {
+        let mut page = BTreeMap::new();
+        let mut text = BTreeMap::new();
+        for (key, value) in class_options.iter().filter_map(|o| class_option_arg(o)) {
+            match key {
+                "font-size" => text.insert("size", value),
+                _ => page.insert(key, value),
+            };
+        }
+        page.extend(
+            self.page
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+        text.extend(
+            self.text
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.clone())),
+        );
+
+        match (self.margin.get("rest"), self.margin.len()) {
+            (_, 0) => {}
+            (Some(rest), 1) => {
+                page.insert("margin", rest.clone());
+            }
+            _ => {
+                let sides = self.margin.iter();
+                let sides = sides.map(|(side, length)| format!("{side}: {length}"));
+                let sides = sides.collect::<Vec<_>>().join(", ");
+                page.insert("margin", format!("({sides})"));
+            }
+        }
+        let numbering = match self.page_style.as_deref() {
+            Some("empty") => Some("none".to_owned()),
+            Some(_) => Some(
+                self.numbering
+                    .clone()
+                    .unwrap_or_else(|| r#""1""#.to_owned()),
+            ),
+            None => self.numbering.clone(),
+        };
+        if let Some(numbering) = numbering {
+            page.insert("numbering", numbering);
+        }
+
+        let par = self
+            .par
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()));
+        write_set(f, "page", page)?;
+        write_set(f, "par", par.collect())?;
+        // the spacing of `par` is only available since Typst 0.12
+        if let Some(spacing) = &self.par_spacing {
+            writeln!(f, "#show par: set block(spacing: {spacing})")?;
+        }
+        write_set(f, "text", text)
+    }
+}
+
+/// Writes `#set func(..)` with the named arguments if any
+fn write_set(f: &mut String, func: &str, args: BTreeMap<&str, String>) -> fmt::Result {
+    if args.is_empty() {
+        return Ok(());
+    }
+    let args = args
+        .into_iter()
+        .map(|(key, value)| format!("{key}: {value}"));
+    writeln!(f, "#set {func}({})", args.collect::<Vec<_>>().join(", "))
+}
+
+/// An array of Typst strings like `("a", "b")`
+fn str_array(values: &[String]) -> Result<String, fmt::Error> {
+    let mut literals = vec![];
+    for value in values {
+        let mut literal = String::new();
+        write_str_literal(&mut literal, value)?;
+        literals.push(literal);
+    }
+    Ok(array(literals.into_iter()))
+}
+
+/// An array of Typst values like `(a, b)`, or `(a,)` for a single value
+fn array(values: impl ExactSizeIterator<Item = String>) -> String {
+    let single = values.len() == 1;
+    let mut array = values.collect::<Vec<_>>().join(", ");
+    if single {
+        array.push(',');
+    }
+    format!("({array})")
+}
@@ -0,0 +1,510 @@
+//! Pluggable handlers converting commands and environments
+//!
+//! The special cases of the converter, e.g. `\item`, `\label` and the
+//! `figure` environment, are registered as default handlers. A handler
+//! registered with the same name replaces the default one.
+
+mod align;
+mod citation;
+mod color;
+mod colspec;
+mod figure;
+mod font;
+mod footnote;
+mod graphics;
+mod ignore;
+mod label;
+mod layout;
+mod length;
+mod link;
+mod list;
+mod matrix;
+mod reference;
+mod section;
+mod tabular;
+mod text;
+mod theorem;
+mod title;
+mod verbatim;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use mitex_parser::spec::CommandSpec;
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use crate::converter::{ConvertError, Converter, LaTeXMode};
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+pub(crate) use align::Align;
+pub(crate) use figure::Figure;
+pub(crate) use list::List;
+pub(crate) use tabular::Tabular;
+pub(crate) use theorem::{Theorem, TheoremEnv};
+pub(crate) use verbatim::Verbatim;
+
+use citation::{Cite, CiteForm};
+use reference::{RefKind, Reference};
+
+/// Converts a command registered by name in [`Handlers`]
+pub trait CommandHandler: Send + Sync {
+    /// Writes the converted command to `f`
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError>;
+}
+
+impl<F> CommandHandler for F
+where
+    F: Fn(&mut ConvertContext<'_>, &mut String, &CmdItem) -> Result<(), ConvertError> + Send + Sync,
+{
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        self(cx, f, cmd)
+    }
+}
+
+/// Converts an environment registered by name in [`Handlers`]
+pub trait EnvHandler: Send + Sync {
+    /// Writes the converted environment to `f`
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError>;
+}
+
+impl<F> EnvHandler for F
+where
+    F: Fn(&mut ConvertContext<'_>, &mut String, &EnvItem) -> Result<(), ConvertError> + Send + Sync,
+{
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        self(cx, f, env)
+    }
+}
+
+/// The state of the converter exposed to handlers
+pub struct ConvertContext<'a> {
+    pub(crate) conv: &'a mut Converter,
+    pub(crate) spec: &'a CommandSpec,
+}
+
+impl<'a> ConvertContext<'a> {
+    pub(crate) fn new(conv: &'a mut Converter, spec: &'a CommandSpec) -> Self {
+        Self { conv, spec }
+    }
+
+    /// The mode of the element being converted
+    pub fn mode(&self) -> LaTeXMode {
+        self.conv.mode
+    }
+
+    /// The command spec used for conversion
+    pub fn spec(&self) -> &CommandSpec {
+        self.spec
+    }
+
+    /// Converts an element, e.g. an argument of the command, in the current
+    /// mode
+    pub fn convert(&mut self, f: &mut String, elem: SyntaxElement) -> Result<(), ConvertError> {
+        self.conv.convert(f, elem, self.spec)
+    }
+
+    /// Converts an element in the given mode
+    pub fn convert_in(
+        &mut self,
+        f: &mut String,
+        elem: SyntaxElement,
+        mode: LaTeXMode,
+    ) -> Result<(), ConvertError> {
+        let prev = self.conv.enter_mode(mode);
+        let res = self.conv.convert(f, elem, self.spec);
+        self.conv.exit_mode(prev);
+        res
+    }
+
+    /// Converts the content of an argument, without the surrounding braces or
+    /// brackets
+    pub fn convert_arg(&mut self, f: &mut String, arg: &SyntaxNode) -> Result<(), ConvertError> {
+        match arg.first_child() {
+            Some(bracket) if bracket.kind() == SyntaxKind::ItemBracket => {
+                for child in bracket.children_with_tokens() {
+                    if matches!(
+                        child.kind(),
+                        SyntaxKind::TokenLBracket | SyntaxKind::TokenRBracket
+                    ) {
+                        continue;
+                    }
+                    self.convert(f, child)?;
+                }
+                Ok(())
+            }
+            _ => self.convert(f, arg.clone().into()),
+        }
+    }
+
+    /// Converts the body of an environment, i.e. the elements between
+    /// `\begin` and `\end`
+    pub fn convert_env_body(&mut self, f: &mut String, env: &EnvItem) -> Result<(), ConvertError> {
+        for child in env.syntax().children_with_tokens() {
+            // skip \begin and \end commands
+            if matches!(child.kind(), SyntaxKind::ItemBegin | SyntaxKind::ItemEnd) {
+                continue;
+            }
+            self.convert(f, child)?;
+        }
+        Ok(())
+    }
+
+    /// Converts a command as if no handler is registered for it, i.e.
+    /// according to its shape in the spec
+    pub fn convert_command(&mut self, f: &mut String, cmd: &CmdItem) -> Result<(), ConvertError> {
+        self.conv
+            .convert_normal_command(f, cmd.syntax().clone().into(), self.spec, None)
+    }
+
+    /// Reports a diagnostic, typically a warning, without stopping the
+    /// conversion
+    pub fn report(&mut self, diag: Diagnostic) {
+        self.conv.diagnostics.push(diag);
+    }
+}
+
+/// A registry of command and environment handlers
+///
+/// The default registry holds the built-in handlers, while
+/// [`Handlers::empty`] holds none, converting everything according to the
+/// spec.
+#[derive(Clone)]
+pub struct Handlers {
+    commands: HashMap<String, Arc<dyn CommandHandler>>,
+    envs: HashMap<String, Arc<dyn EnvHandler>>,
+}
+
+impl Default for Handlers {
+    fn default() -> Self {
+        let mut handlers = Self::empty();
+        for name in [
+            "part",
+            "chapter",
+            "section",
+            "subsection",
+            "subsubsection",
+            "paragraph",
+            "subparagraph",
+        ] {
+            handlers
+                .register_command(name, section::Heading)
+                .register_command(format!("{name}*"), section::Heading);
+        }
+        for (name, form) in [
+            ("cite", CiteForm::Normal),
+            ("citep", CiteForm::Normal),
+            ("citep*", CiteForm::Normal),
+            ("Citep", CiteForm::Normal),
+            ("citealp", CiteForm::Normal),
+            ("parencite", CiteForm::Normal),
+            ("Parencite", CiteForm::Normal),
+            ("autocite", CiteForm::Normal),
+            ("Autocite", CiteForm::Normal),
+            ("citet", CiteForm::Prose),
+            ("citet*", CiteForm::Prose),
+            ("Citet", CiteForm::Prose),
+            ("citealt", CiteForm::Prose),
+            ("textcite", CiteForm::Prose),
+            ("Textcite", CiteForm::Prose),
+            ("citeauthor", CiteForm::Author),
+            ("citeauthor*", CiteForm::Author),
+            ("Citeauthor", CiteForm::Author),
+            ("citeyear", CiteForm::Year),
+            ("citeyearpar", CiteForm::Year),
+            ("nocite", CiteForm::None),
+        ] {
+            handlers.register_command(name, Cite(form));
+        }
+        for name in [
+            "array", "matrix*", "pmatrix*", "bmatrix*", "Bmatrix*", "vmatrix*", "Vmatrix*",
+        ] {
+            handlers.register_env(name, matrix::Matrix);
+        }
+        for (name, func) in [
+            ("bf", "#strong"),
+            ("bfseries", "#strong"),
+            ("it", "#emph"),
+            ("itshape", "#emph"),
+            ("em", "#emph"),
+            ("tt", font::MONO),
+            ("ttfamily", font::MONO),
+            ("sf", font::SANS),
+            ("sffamily", font::SANS),
+            ("upshape", "#text(style: \"normal\")"),
+            ("mdseries", "#text(weight: \"regular\")"),
+            ("sc", "#smallcaps"),
+            ("scshape", "#smallcaps"),
+        ] {
+            handlers.register_command(name, font::Declaration(func));
+        }
+        for (name, size) in [
+            ("tiny", 0.5),
+            ("scriptsize", 0.7),
+            ("footnotesize", 0.8),
+            ("small", 0.9),
+            ("normalsize", 1.0),
+            ("large", 1.2),
+            ("Large", 1.44),
+            ("LARGE", 1.728),
+            ("huge", 2.074),
+            ("Huge", 2.488),
+        ] {
+            handlers.register_command(name, font::FontSize(size));
+        }
+        for name in [
+            "mathrm",
+            "operatorname",
+            "operatorname*",
+            "operatornamewithlimits",
+        ] {
+            handlers.register_command(name, font::MathFont);
+        }
+        for (name, escaped) in [("#", "\\#"), ("$", "\\$"), ("%", "%"), ("&", "\\&")] {
+            handlers.register_command(name, text::TextSymbol(escaped));
+        }
+        for name in ["verb", "verb*", "lstinline", "mintinline"] {
+            handlers.register_command(name, verbatim::Verb);
+        }
+        handlers
+            .register_command("item", list::Item)
+            .register_command("label", label::Label)
+            .register_command("includegraphics", graphics::IncludeGraphics)
+            .register_command("textbf", font::TextFont("#strong"))
+            .register_command("textit", font::TextFont("#emph"))
+            .register_command("texttt", font::TextFont(font::MONO))
+            .register_command("textsf", font::TextFont(font::SANS))
+            .register_command("underline", font::TextFont("#underline"))
+            .register_command("color", color::Color)
+            .register_command("textcolor", color::TextColor)
+            .register_command("colorbox", color::ColorBox { framed: false })
+            .register_command("fcolorbox", color::ColorBox { framed: true })
+            .register_command("footnote", footnote::Footnote)
+            .register_command("footnotemark", footnote::FootnoteMark)
+            .register_command("footnotetext", footnote::FootnoteText)
+            .register_command("href", link::Href)
+            .register_command("url", link::Href)
+            .register_command("nolinkurl", link::NoLinkUrl)
+            .register_command("hyperlink", link::HyperLink)
+            .register_command("hypertarget", link::HyperTarget)
+            .register_command("documentclass", title::DocumentClass)
+            .register_command("usepackage", layout::UsePackage)
+            .register_command("geometry", layout::Geometry)
+            .register_command("setlength", layout::SetLength)
+            .register_command("linespread", layout::LineSpread)
+            .register_command("setstretch", layout::LineSpread)
+            .register_command("singlespacing", layout::LineSpread)
+            .register_command("onehalfspacing", layout::LineSpread)
+            .register_command("doublespacing", layout::LineSpread)
+            .register_command("pagestyle", layout::PageStyle)
+            .register_command("thispagestyle", ignore::Silent)
+            .register_command("pagenumbering", layout::PageNumbering)
+            .register_command("setmainlanguage", layout::MainLanguage)
+            .register_command("setdefaultlanguage", layout::MainLanguage)
+            .register_command("setotherlanguage", ignore::Silent)
+            .register_command("setotherlanguages", ignore::Silent)
+            .register_command("selectlanguage", ignore::Silent)
+            .register_command("title", title::TitleField)
+            .register_command("date", title::TitleField)
+            .register_command("author", title::AuthorField)
+            .register_command("affiliation", title::Affiliation)
+            .register_command("keywords", title::Keywords)
+            .register_command("maketitle", title::MakeTitle)
+            .register_command("today", title::Today)
+            .register_command("newtheorem", theorem::NewTheorem)
+            .register_command("newtheorem*", theorem::NewTheorem)
+            .register_command("theoremstyle", theorem::TheoremStyle)
+            .register_command("definecolor", color::DefineColor)
+            .register_command("colorlet", color::DefineColor)
+            .register_command("ldots", text::TextSymbol("…"))
+            .register_command("dots", text::TextSymbol("…"))
+            .register_command(",", text::TextSymbol("\u{202f}"))
+            .register_command("thinspace", text::TextSymbol("\u{202f}"))
+            .register_command("@", text::TextSymbol(""))
+            .register_command("ref", Reference(RefKind::Number))
+            .register_command("eqref", Reference(RefKind::Equation))
+            .register_command("autoref", Reference(RefKind::Supplement))
+            .register_command("cref", Reference(RefKind::Supplement))
+            .register_command("Cref", Reference(RefKind::Supplement))
+            .register_command("pageref", Reference(RefKind::Page))
+            .register_command("intertext", align::Intertext)
+            .register_command("shortintertext", align::Intertext)
+            .register_command("appendix", section::Appendix)
+            .register_command("tableofcontents", section::Outline("indent: auto"))
+            .register_command(
+                "listoffigures",
+                section::Outline("title: [List of Figures], target: figure.where(kind: image)"),
+            )
+            .register_command(
+                "listoftables",
+                section::Outline("title: [List of Tables], target: figure.where(kind: table)"),
+            )
+            .register_command("bibliography", citation::Bibliography)
+            .register_command("printbibliography", citation::Bibliography)
+            .register_command("bibliographystyle", ignore::Silent)
+            .register_command("addbibresource", ignore::Silent)
+            .register_env("thebibliography", citation::TheBibliography)
+            .register_env("document", title::Document)
+            .register_env("abstract", title::Abstract)
+            .register_env("IEEEkeywords", title::KeywordsEnv)
+            .register_env("proof", theorem::Proof)
+            .register_env("figure", Figure)
+            .register_env("table", Figure);
+        handlers
+    }
+}
+
+impl fmt::Debug for Handlers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut commands = self.commands.keys().collect::<Vec<_>>();
+        commands.sort();
+        let mut envs = self.envs.keys().collect::<Vec<_>>();
+        envs.sort();
+        f.debug_struct("Handlers")
+            .field("commands", &commands)
+            .field("envs", &envs)
+            .finish()
+    }
+}
+
+impl Handlers {
+    /// Creates a registry without any handler
+    pub fn empty() -> Self {
+        Self {
+            commands: HashMap::new(),
+            envs: HashMap::new(),
+        }
+    }
+
+    /// Registers a handler for the command `\name`
+    ///
+    /// The command should also be defined in the spec so that its arguments
+    /// are parsed.
+    pub fn register_command(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl CommandHandler + 'static,
+    ) -> &mut Self {
+        self.commands.insert(name.into(), Arc::new(handler));
+        self
+    }
+
+    /// Registers a handler for the environment `name`
+    ///
+    /// The environment should also be defined in the spec so that its
+    /// arguments are parsed.
+    pub fn register_env(
+        &mut self,
+        name: impl Into<String>,
+        handler: impl EnvHandler + 'static,
+    ) -> &mut Self {
+        self.envs.insert(name.into(), Arc::new(handler));
+        self
+    }
+
+    /// Removes the handler for the command `\name`
+    pub fn remove_command(&mut self, name: &str) -> &mut Self {
+        self.commands.remove(name);
+        self
+    }
+
+    /// Removes the handler for the environment `name`
+    pub fn remove_env(&mut self, name: &str) -> &mut Self {
+        self.envs.remove(name);
+        self
+    }
+
+    pub(crate) fn command(&self, name: &str) -> Option<Arc<dyn CommandHandler>> {
+        self.commands.get(name).cloned()
+    }
+
+    pub(crate) fn env(&self, name: &str) -> Option<Arc<dyn EnvHandler>> {
+        self.envs.get(name).cloned()
+    }
+}
+
+/// The Typst name of an environment, which is its alias in the spec if any
+fn env_alias(spec: &CommandSpec, env: &EnvItem) -> String {
+    let name = env
+        .name_tok()
+        .expect("environment name must be non-empty")
+        .text()
+        .trim()
+        .to_owned();
+    match spec.get_env(&name).and_then(|shape| shape.alias.as_deref()) {
+        Some(alias) => alias.to_owned(),
+        None => name,
+    }
+}
+
+/// The trimmed text of an argument without the surrounding braces or
+/// brackets
+fn arg_text(arg: &SyntaxNode) -> String {
+    let text = arg.text().to_string();
+    let text = text.trim();
+    let text = text
+        .strip_prefix(['{', '['])
+        .and_then(|text| text.strip_suffix(['}', ']']))
+        .unwrap_or(text);
+    text.trim().to_owned()
+}
+
+/// Splits options like `a4paper, label={(\alph*)}` by the commas not in
+/// braces into keys and optional values
+fn split_options(text: &str) -> Vec<(&str, Option<&str>)> {
+    let mut options = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                options.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    options.push(&text[start..]);
+    options
+        .into_iter()
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(|option| match option.split_once('=') {
+            Some((key, value)) => (key.trim(), Some(value.trim())),
+            None => (option, None),
+        })
+        .collect()
+}
+
+/// Reports a warning of the unsupported input at a node, e.g. an argument
+fn report_unsupported(cx: &mut ConvertContext<'_>, node: &SyntaxNode, message: impl Into<String>) {
+    let range = node.text_range();
+    cx.report(Diagnostic::warning(
+        DiagnosticCode::Unsupported,
+        message,
+        range.start().into()..range.end().into(),
+    ));
+}
@@ -0,0 +1,235 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxKind};
+use rowan::ast::AstNode;
+
+use super::label::{label_name, missing_label};
+use super::{CommandHandler, ConvertContext, EnvHandler};
+use crate::converter::{ConvertError, LaTeXEnv, LaTeXMode};
+
+/// A row of an alignment environment
+#[derive(Default)]
+struct Row {
+    cells: Vec<String>,
+    numbered: bool,
+    tag: Option<String>,
+    label: Option<String>,
+    intertext: Option<String>,
+}
+
+impl Row {
+    fn new() -> Self {
+        Self {
+            cells: vec![String::new()],
+            numbered: true,
+            ..Default::default()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cells.iter().all(|cell| cell.trim().is_empty())
+            && self.tag.is_none()
+            && self.label.is_none()
+            && self.intertext.is_none()
+    }
+}
+
+/// Converts alignment environments like `align` in text mode to equations
+/// numbered row by row
+///
+/// Each row can carry its own `\label`, `\tag{..}` and `\notag`, and rows
+/// of `\intertext{..}` are put between the equations. If no row is numbered,
+/// labeled or tagged, the environment is converted to a single unnumbered
+/// equation with Typst's own alignment points.
+pub struct Align;
+
+impl EnvHandler for Align {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let name = env.name_tok().unwrap();
+        let name = name.text().trim();
+        let base = name.trim_end_matches('*');
+        let numbered = !name.ends_with('*')
+            && matches!(
+                base,
+                "align" | "alignat" | "flalign" | "eqnarray" | "gather" | "multline"
+            );
+
+        let mut rows = vec![];
+        let mut row = Row::new();
+        let prev_env = cx.conv.enter_env(LaTeXEnv::Math);
+        let prev_mode = cx.conv.enter_mode(LaTeXMode::Math);
+        let res: Result<(), ConvertError> = (|| {
+            let mut after_newline = false;
+            let mut in_spacing = false;
+            for child in env.syntax().children_with_tokens() {
+                // skip the spacing of `\\[2pt]`
+                if std::mem::take(&mut after_newline) && child.kind() == SyntaxKind::TokenLBracket {
+                    in_spacing = true;
+                }
+                if in_spacing {
+                    in_spacing = child.kind() != SyntaxKind::TokenRBracket;
+                    continue;
+                }
+                match child.kind() {
+                    SyntaxKind::ItemBegin | SyntaxKind::ItemEnd => continue,
+                    SyntaxKind::TokenAmpersand => {
+                        row.cells.push(String::new());
+                        continue;
+                    }
+                    SyntaxKind::ItemNewLine => {
+                        rows.push(std::mem::replace(&mut row, Row::new()));
+                        after_newline = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+                let cmd = child.as_node().cloned().and_then(CmdItem::cast);
+                let cmd_name = cmd
+                    .as_ref()
+                    .and_then(|cmd| cmd.name_tok())
+                    .map(|name| name.text()[1..].to_owned());
+                match (cmd, cmd_name.as_deref()) {
+                    (Some(cmd), Some("label")) => {
+                        row.label = Some(label_name(&cmd).ok_or_else(|| missing_label(&cmd))?);
+                    }
+                    (Some(_), Some("notag" | "nonumber")) => row.numbered = false,
+                    (Some(cmd), Some(tag @ ("tag" | "tag*"))) => {
+                        let text = text_arg(cx, &cmd)?;
+                        row.tag = Some(match tag {
+                            "tag" => format!("({})", text.trim()),
+                            _ => text.trim().to_owned(),
+                        });
+                    }
+                    (Some(cmd), Some("intertext" | "shortintertext")) => {
+                        let text = text_arg(cx, &cmd)?;
+                        if !row.is_empty() {
+                            rows.push(std::mem::replace(&mut row, Row::new()));
+                        }
+                        rows.push(Row {
+                            intertext: Some(text.trim().to_owned()),
+                            ..Row::new()
+                        });
+                    }
+                    _ => {
+                        let cell = row.cells.last_mut().unwrap();
+                        cx.convert(cell, child)?;
+                    }
+                }
+            }
+            Ok(())
+        })();
+        cx.conv.exit_mode(prev_mode);
+        cx.conv.exit_env(prev_env);
+        res?;
+        rows.push(row);
+        rows.retain(|row| !row.is_empty());
+
+        if !numbered {
+            for row in &mut rows {
+                row.numbered = false;
+            }
+        }
+        // multline is numbered as a whole at the last row
+        if base == "multline" {
+            let numbered = rows.iter().all(|row| row.numbered);
+            let tag = rows.iter_mut().find_map(|row| row.tag.take());
+            let label = rows.iter_mut().find_map(|row| row.label.take());
+            for row in &mut rows {
+                row.numbered = false;
+            }
+            if let Some(last) = rows.last_mut() {
+                last.numbered = numbered;
+                last.tag = tag;
+                last.label = label;
+            }
+        }
+        if !cx.conv.emit_labels {
+            for row in &mut rows {
+                row.label = None;
+            }
+        }
+
+        let plain = rows.iter().all(|row| {
+            !row.numbered && row.tag.is_none() && row.label.is_none() && row.intertext.is_none()
+        });
+        if plain {
+            f.write_str("#math.equation(block: true, numbering: none, $ ")?;
+            for (index, row) in rows.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(" \\ ")?;
+                }
+                let cells = row.cells.iter().map(|cell| cell.trim()).collect::<Vec<_>>();
+                f.write_str(&cells.join(" & "))?;
+            }
+            f.write_str(" $);")?;
+            return Ok(());
+        }
+
+        cx.conv.helpers.insert("mitex-align");
+        f.write_str("#mitex-align(\n")?;
+        for row in &rows {
+            if let Some(intertext) = &row.intertext {
+                writeln!(f, "  (intertext: [{intertext}]),")?;
+                continue;
+            }
+            f.write_str("  (cells: (")?;
+            for cell in &row.cells {
+                write!(f, "${}$, ", cell.trim())?;
+            }
+            f.write_char(')')?;
+            if let Some(tag) = &row.tag {
+                write!(f, ", tag: [{tag}]")?;
+            } else if !row.numbered {
+                f.write_str(", numbered: false")?;
+            }
+            if let Some(label) = &row.label {
+                write!(f, ", label: <{label}>")?;
+            }
+            f.write_str("),\n")?;
+        }
+        match base {
+            "eqnarray" => f.write_str("  aligns: (right, center, left),\n")?,
+            "gather" | "multline" | "gathered" => f.write_str("  aligns: (center,),\n")?,
+            _ => {}
+        }
+        f.write_str(");")?;
+        Ok(())
+    }
+}
+
+/// Converts the argument of a command like `\tag{..}` in text mode
+fn text_arg(cx: &mut ConvertContext<'_>, cmd: &CmdItem) -> Result<String, ConvertError> {
+    let mut text = String::new();
+    if let Some(arg) = cmd.arguments().next() {
+        let prev = cx.conv.enter_mode(LaTeXMode::Text);
+        let res = cx.convert_arg(&mut text, &arg);
+        cx.conv.exit_mode(prev);
+        res?;
+    }
+    Ok(text)
+}
+
+/// Converts command `\intertext{..}` in math mode, which puts the text
+/// between the rows of an alignment environment
+pub struct Intertext;
+
+impl CommandHandler for Intertext {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let text = text_arg(cx, cmd)?;
+        match cx.mode() {
+            LaTeXMode::Text => f.write_str(&text)?,
+            LaTeXMode::Math => write!(f, "#[{}] \\ ", text.trim())?,
+        }
+        Ok(())
+    }
+}
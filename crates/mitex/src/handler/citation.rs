@@ -0,0 +1,310 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::label::write_label;
+use super::{arg_text, CommandHandler, ConvertContext, EnvHandler};
+use crate::bibliography::BibItem;
+use crate::converter::{write_str_literal, ConvertError, LaTeXMode};
+
+/// The form of a citation, i.e. the `form` argument of Typst's `cite`
+#[derive(Debug, Clone, Copy)]
+pub enum CiteForm {
+    /// `\cite` and `\citep`, e.g. "[1]" or "(Knuth, 1984)"
+    Normal,
+    /// `\citet` and `\textcite`, e.g. "Knuth (1984)"
+    Prose,
+    /// `\citeauthor`, e.g. "Knuth"
+    Author,
+    /// `\citeyear`, e.g. "1984"
+    Year,
+    /// `\nocite`, which adds the entry to the bibliography only
+    None,
+}
+
+/// Converts citation commands like `\citep[see][p. 5]{key}` to Typst
+/// citations
+///
+/// With one optional argument it is the postnote, i.e. the supplement, and
+/// with two the first one is the prenote, which is written before the
+/// citation. The argument is a comma-separated list of keys, which are cited
+/// together.
+pub struct Cite(pub CiteForm);
+
+impl CommandHandler for Cite {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let mut notes = vec![];
+        let mut keys = None;
+        for arg in cmd.arguments() {
+            match arg.first_child().map(|node| node.kind()) {
+                Some(SyntaxKind::ItemBracket) => notes.push(arg),
+                _ => keys = Some(arg),
+            }
+        }
+        let (prenote, postnote) = match notes.len() {
+            0 => (None, None),
+            1 => (None, notes.pop()),
+            _ => (Some(notes.remove(0)), Some(notes.remove(0))),
+        };
+        let keys = keys.map(|arg| arg_text(&arg)).unwrap_or_default();
+        let keys = keys
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty() && *key != "*")
+            .collect::<Vec<_>>();
+
+        if let Some(prenote) = prenote {
+            let mut pre = String::new();
+            cx.convert_arg(&mut pre, &prenote)?;
+            if !pre.trim().is_empty() {
+                f.write_str(pre.trim())?;
+                f.write_char(' ')?;
+            }
+        }
+        let mut supplement = String::new();
+        if let Some(postnote) = postnote {
+            cx.convert_arg(&mut supplement, &postnote)?;
+        }
+        let supplement = supplement.trim();
+        for (index, key) in keys.iter().enumerate() {
+            f.write_str("#cite(")?;
+            write_label(f, key)?;
+            match self.0 {
+                CiteForm::Normal => {}
+                CiteForm::Prose => f.write_str(", form: \"prose\"")?,
+                CiteForm::Author => f.write_str(", form: \"author\"")?,
+                CiteForm::Year => f.write_str(", form: \"year\"")?,
+                CiteForm::None => f.write_str(", form: none")?,
+            }
+            // the postnote applies to the whole list in LaTeX
+            if index + 1 == keys.len() && !supplement.is_empty() {
+                write!(f, ", supplement: [{supplement}]")?;
+            }
+            f.write_char(')')?;
+        }
+        // end the last call in case of a following `[` or `(`
+        if !keys.is_empty() {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts commands `\bibliography{a,b}` and `\printbibliography` to a
+/// Typst bibliography
+///
+/// The style is taken from `\bibliographystyle`, and the files of
+/// `\printbibliography` from `\addbibresource`, wherever they are in the
+/// document.
+pub struct Bibliography;
+
+impl CommandHandler for Bibliography {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let root = cmd.syntax().ancestors().last().unwrap();
+        let name = cmd.name_tok().unwrap();
+        let files = match name.text() {
+            "\\bibliography" => cmd.arguments().last().map(|arg| arg_text(&arg)),
+            _ => {
+                let files = find_commands(&root, "\\addbibresource")
+                    .filter_map(|cmd| cmd.arguments().last())
+                    .map(|arg| arg_text(&arg))
+                    .collect::<Vec<_>>();
+                Some(files.join(","))
+            }
+        };
+        let files = files
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|file| !file.is_empty())
+            .map(|file| {
+                if file.ends_with(".bib") {
+                    file.to_owned()
+                } else {
+                    format!("{file}.bib")
+                }
+            })
+            .collect::<Vec<_>>();
+
+        f.write_str("#bibliography(")?;
+        match files.as_slice() {
+            [file] => write_str_literal(f, file)?,
+            files => {
+                f.write_char('(')?;
+                for (index, file) in files.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_str_literal(f, file)?;
+                }
+                f.write_char(')')?;
+            }
+        }
+        write_bibliography_args(f, &root)?;
+        f.write_str(");")?;
+        Ok(())
+    }
+}
+
+/// Converts environment `thebibliography` to a Typst bibliography loaded from
+/// a Hayagriva file
+///
+/// The entries are collected in [`crate::Converted::bib_items`], which the
+/// caller is expected to write to the file set by
+/// [`crate::ConvertOptions::bibliography_file`].
+pub struct TheBibliography;
+
+impl EnvHandler for TheBibliography {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let mut items: Vec<BibItem> = vec![];
+        for child in env.syntax().children_with_tokens() {
+            if matches!(child.kind(), SyntaxKind::ItemBegin | SyntaxKind::ItemEnd) {
+                continue;
+            }
+            let bibitem = child
+                .as_node()
+                .cloned()
+                .and_then(CmdItem::cast)
+                .filter(|cmd| {
+                    cmd.name_tok()
+                        .is_some_and(|name| name.text() == "\\bibitem")
+                });
+            match bibitem {
+                Some(cmd) => {
+                    let mut key = String::new();
+                    let mut label = None;
+                    for arg in cmd.arguments() {
+                        match arg.first_child().map(|node| node.kind()) {
+                            Some(SyntaxKind::ItemBracket) => {
+                                label = Some(arg_text(&arg).trim().to_owned())
+                            }
+                            _ => key = arg_text(&arg).trim().to_owned(),
+                        }
+                    }
+                    items.push(BibItem {
+                        key,
+                        label,
+                        text: String::new(),
+                    });
+                }
+                None => {
+                    if let Some(item) = items.last_mut() {
+                        push_plain_text(&mut item.text, &child);
+                    }
+                }
+            }
+        }
+        for item in &mut items {
+            item.text = collapse_spaces(&item.text);
+        }
+        cx.conv.bib_items.extend(items);
+
+        let root = env.syntax().ancestors().last().unwrap();
+        f.write_str("#bibliography(")?;
+        write_str_literal(f, &cx.conv.bibliography_file)?;
+        write_bibliography_args(f, &root)?;
+        f.write_str(");")?;
+        Ok(())
+    }
+}
+
+/// Writes the optional arguments of `bibliography` found in the document,
+/// i.e. the style and whether to list all entries by `\nocite{*}`
+fn write_bibliography_args(f: &mut String, root: &SyntaxNode) -> Result<(), ConvertError> {
+    let style = find_commands(root, "\\bibliographystyle")
+        .filter_map(|cmd| cmd.arguments().last())
+        .filter_map(|arg| bibliography_style(arg_text(&arg).trim()))
+        .last();
+    if let Some(style) = style {
+        f.write_str(", style: ")?;
+        write_str_literal(f, style)?;
+    }
+    let full = find_commands(root, "\\nocite")
+        .filter_map(|cmd| cmd.arguments().last())
+        .any(|arg| arg_text(&arg).split(',').any(|key| key.trim() == "*"));
+    if full {
+        f.write_str(", full: true")?;
+    }
+    Ok(())
+}
+
+/// Maps a BibTeX style to the closest Typst style
+fn bibliography_style(style: &str) -> Option<&'static str> {
+    Some(match style {
+        "plain" | "abbrv" | "unsrt" | "alpha" | "ieeetr" | "IEEEtran" | "IEEEtranS" => "ieee",
+        "plainnat" | "abbrvnat" | "unsrtnat" | "chicago" => "chicago-author-date",
+        "apalike" | "apa" | "apacite" => "apa",
+        "acm" | "ACM-Reference-Format" => "association-for-computing-machinery",
+        "siam" | "amsplain" | "amsalpha" => "american-mathematical-society",
+        "nature" => "nature",
+        "vancouver" => "vancouver",
+        "splncs04" | "splncs" => "springer-lecture-notes-in-computer-science",
+        "elsarticle-num" => "elsevier-with-titles",
+        "elsarticle-harv" => "elsevier-harvard",
+        _ => return None,
+    })
+}
+
+fn find_commands<'a>(root: &SyntaxNode, name: &'a str) -> impl Iterator<Item = CmdItem> + 'a {
+    root.descendants()
+        .filter_map(CmdItem::cast)
+        .filter(move |cmd| cmd.name_tok().is_some_and(|tok| tok.text() == name))
+}
+
+/// Appends the text of an element with the markup stripped
+pub(super) fn push_plain_text(text: &mut String, elem: &SyntaxElement) {
+    match elem {
+        SyntaxElement::Node(node) => {
+            for child in node.children_with_tokens() {
+                push_plain_text(text, &child);
+            }
+        }
+        SyntaxElement::Token(token) => match token.kind() {
+            SyntaxKind::TokenLBrace
+            | SyntaxKind::TokenRBrace
+            | SyntaxKind::TokenComment
+            | SyntaxKind::TokenDollar
+            | SyntaxKind::TokenBeginMath
+            | SyntaxKind::TokenEndMath => {}
+            SyntaxKind::TokenTilde | SyntaxKind::TokenLineBreak => text.push(' '),
+            // keep escaped characters like `\&` and logos like `\TeX`
+            SyntaxKind::ClauseCommandName => {
+                let name = &token.text()[1..];
+                if (name.len() == 1 && !name.chars().all(char::is_alphabetic))
+                    || matches!(name, "TeX" | "LaTeX" | "BibTeX")
+                {
+                    text.push_str(name);
+                }
+            }
+            _ => text.push_str(token.text()),
+        },
+    }
+}
+
+/// Collapses the runs of whitespace to single spaces, trimming both ends
+pub(super) fn collapse_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
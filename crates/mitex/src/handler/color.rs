@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, SyntaxElement, SyntaxKind, SyntaxNode};
+
+use super::length::number_str;
+use super::{arg_text, CommandHandler, ConvertContext};
+use crate::converter::{ConvertError, LaTeXMode};
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// The colors predefined by xcolor, which differ from the ones of Typst
+const PREDEFINED: &[(&str, &str)] = &[
+    ("red", "rgb(100%, 0%, 0%)"),
+    ("green", "rgb(0%, 100%, 0%)"),
+    ("blue", "rgb(0%, 0%, 100%)"),
+    ("cyan", "cmyk(100%, 0%, 0%, 0%)"),
+    ("magenta", "cmyk(0%, 100%, 0%, 0%)"),
+    ("yellow", "cmyk(0%, 0%, 100%, 0%)"),
+    ("black", "luma(0%)"),
+    ("white", "luma(100%)"),
+    ("gray", "luma(50%)"),
+    ("darkgray", "luma(25%)"),
+    ("lightgray", "luma(75%)"),
+    ("brown", "rgb(75%, 50%, 25%)"),
+    ("lime", "rgb(75%, 100%, 0%)"),
+    ("olive", "rgb(50%, 50%, 0%)"),
+    ("orange", "rgb(100%, 50%, 0%)"),
+    ("pink", "rgb(100%, 75%, 75%)"),
+    ("purple", "rgb(75%, 0%, 25%)"),
+    ("teal", "rgb(0%, 50%, 50%)"),
+    ("violet", "rgb(50%, 0%, 50%)"),
+];
+
+/// Converts command `\color{red}`, which colors the rest of the enclosing
+/// group, to `#text(fill: ..)[..]`
+pub struct Color;
+
+impl CommandHandler for Color {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        // the greedy argument starts with the optional model and the color
+        let mut rest = cmd
+            .arguments()
+            .flat_map(|arg| arg.children_with_tokens())
+            .skip_while(|elem| elem.kind() == SyntaxKind::TokenWhiteSpace)
+            .peekable();
+        let mut model = None;
+        if rest
+            .next_if(|elem| elem.kind() == SyntaxKind::TokenLBracket)
+            .is_some()
+        {
+            let mut text = String::new();
+            for elem in rest.by_ref() {
+                if elem.kind() == SyntaxKind::TokenRBracket {
+                    break;
+                }
+                text.push_str(&elem.to_string());
+            }
+            model = Some(text);
+        }
+        while rest
+            .next_if(|elem| elem.kind() == SyntaxKind::TokenWhiteSpace)
+            .is_some()
+        {}
+        let Some(SyntaxElement::Node(color)) =
+            rest.next_if(|elem| elem.kind() == SyntaxKind::ItemCurly)
+        else {
+            return cx.convert_command(f, cmd);
+        };
+        let color = parse_color(cx, model.as_deref(), &color);
+        let body = rest.skip_while(|elem| elem.kind() == SyntaxKind::TokenWhiteSpace);
+
+        let Some(color) = color else {
+            for elem in body {
+                cx.convert(f, elem)?;
+            }
+            return Ok(());
+        };
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            write!(f, "text(fill: #{color}, ")?;
+            for elem in body {
+                cx.convert(f, elem)?;
+            }
+            f.push(')');
+        } else {
+            write!(f, "#text(fill: {color})[")?;
+            for elem in body {
+                cx.convert(f, elem)?;
+            }
+            f.push_str("];");
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\textcolor{red}{text}` to `#text(fill: ..)[text]`
+pub struct TextColor;
+
+impl CommandHandler for TextColor {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let (model, args) = color_args(cmd);
+        let [color, body] = &args[..] else {
+            return cx.convert_command(f, cmd);
+        };
+        let Some(color) = parse_color(cx, model.as_deref(), color) else {
+            return cx.convert_arg(f, body);
+        };
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            write!(f, "text(fill: #{color}, ")?;
+            cx.convert_arg(f, body)?;
+            f.push(')');
+        } else {
+            write!(f, "#text(fill: {color})[")?;
+            cx.convert_arg(f, body)?;
+            f.push_str("];");
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\colorbox{yellow}{text}` and the framed
+/// `\fcolorbox{red}{yellow}{text}` to `#box(fill: ..)[text]`
+pub struct ColorBox {
+    /// Whether the box has a frame, i.e. `\fcolorbox`
+    pub framed: bool,
+}
+
+impl CommandHandler for ColorBox {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let (model, args) = color_args(cmd);
+        let (frame, fill, body) = match (&args[..], self.framed) {
+            ([frame, fill, body], true) => (Some(frame), fill, body),
+            ([fill, body], false) => (None, fill, body),
+            _ => return cx.convert_command(f, cmd),
+        };
+        let frame = frame.map(|frame| parse_color(cx, model.as_deref(), frame));
+        let fill = parse_color(cx, model.as_deref(), fill);
+
+        // the padding of `\fboxsep` and the rule of `\fboxrule`
+        let mut attrs = String::new();
+        if let Some(fill) = fill {
+            write!(attrs, "fill: {fill}, ")?;
+        }
+        if let Some(Some(frame)) = frame {
+            write!(attrs, "stroke: 0.4pt + {frame}, ")?;
+        }
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            write!(f, "#box({attrs}inset: 3pt, $")?;
+            cx.convert_arg(f, body)?;
+            f.push_str("$)");
+        } else {
+            write!(f, "#box({attrs}inset: 3pt)[")?;
+            cx.convert_arg(f, body)?;
+            f.push_str("];");
+        }
+        Ok(())
+    }
+}
+
+/// Records the color defined by `\definecolor{name}{HTML}{FF8800}` or
+/// `\colorlet{name}{red!50}` for the following commands, without output
+pub struct DefineColor;
+
+impl CommandHandler for DefineColor {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        // the optional type in brackets is ignored
+        let (_, args) = color_args(cmd);
+        let (name, model, color) = match &args[..] {
+            [name, model, color] => (name, Some(arg_text(model)), color),
+            // `\colorlet` with an expression
+            [name, color] => (name, None, color),
+            _ => return Ok(()),
+        };
+        if let Some(color) = parse_color(cx, model.as_deref(), color) {
+            cx.conv.colors.insert(arg_text(name), color);
+        }
+        Ok(())
+    }
+}
+
+/// The optional model in brackets and the curly arguments of a command
+fn color_args(cmd: &CmdItem) -> (Option<String>, Vec<SyntaxNode>) {
+    let mut model = None;
+    let mut args = vec![];
+    for arg in cmd.arguments() {
+        match arg.first_child().map(|child| child.kind()) {
+            Some(SyntaxKind::ItemBracket) => model = Some(arg_text(&arg)),
+            _ => args.push(arg),
+        }
+    }
+    (model, args)
+}
+
+/// Converts the color of an argument to Typst, reporting a warning if it
+/// cannot be converted
+fn parse_color(
+    cx: &mut ConvertContext<'_>,
+    model: Option<&str>,
+    arg: &SyntaxNode,
+) -> Option<String> {
+    let spec = arg_text(arg);
+    let color = match model {
+        Some(model) => model_color(model, &spec),
+        None => expr_color(&cx.conv.colors, &spec),
+    };
+    color
+        .map_err(|(code, message)| {
+            let range = arg.text_range();
+            cx.report(Diagnostic::warning(
+                code,
+                message,
+                range.start().into()..range.end().into(),
+            ));
+        })
+        .ok()
+}
+
+/// Converts a color given by a model like `rgb` and its values like
+/// `0.1,0.2,0.3`
+fn model_color(model: &str, spec: &str) -> Result<String, (DiagnosticCode, String)> {
+    let invalid = || {
+        (
+            DiagnosticCode::Unsupported,
+            format!("invalid color of model {model}: {spec}"),
+        )
+    };
+    let values = spec
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>();
+    let numbers = |len: usize| {
+        let numbers = values
+            .iter()
+            .map(|value| value.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        match numbers.len() == len {
+            true => Ok(numbers),
+            false => Err(invalid()),
+        }
+    };
+    let percents = |len: usize| -> Result<String, (DiagnosticCode, String)> {
+        let numbers = numbers(len)?;
+        let percents = numbers
+            .iter()
+            .map(|value| format!("{}%", number_str(value * 100.0)));
+        Ok(percents.collect::<Vec<_>>().join(", "))
+    };
+    Ok(match model {
+        "rgb" => format!("rgb({})", percents(3)?),
+        "cmyk" => format!("cmyk({})", percents(4)?),
+        "gray" => format!("luma({})", percents(1)?),
+        "RGB" => {
+            let numbers = numbers(3)?;
+            let numbers = numbers.iter().map(|value| number_str(*value));
+            format!("rgb({})", numbers.collect::<Vec<_>>().join(", "))
+        }
+        "HTML" => {
+            let hex = spec.trim();
+            if hex.len() != 6 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            format!("rgb(\"#{hex}\")")
+        }
+        _ => {
+            return Err((
+                DiagnosticCode::Unsupported,
+                format!("unsupported color model: {model}"),
+            ))
+        }
+    })
+}
+
+/// Converts an xcolor expression like `red!30!blue`, which mixes 30% of red
+/// with blue, or with white if the last color is omitted
+fn expr_color(
+    colors: &HashMap<String, String>,
+    expr: &str,
+) -> Result<String, (DiagnosticCode, String)> {
+    let named = |name: &str| {
+        let name = name.trim();
+        let predefined = PREDEFINED
+            .iter()
+            .find(|(predefined, _)| *predefined == name);
+        match colors.get(name) {
+            Some(color) => Ok(color.clone()),
+            None => predefined
+                .map(|(_, color)| color.to_string())
+                .ok_or_else(|| {
+                    (
+                        DiagnosticCode::UnknownColor,
+                        format!("unknown color: {name}"),
+                    )
+                }),
+        }
+    };
+    let mut parts = expr.split('!');
+    let mut color = named(parts.next().unwrap_or_default())?;
+    while let Some(percent) = parts.next() {
+        let percent = percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|percent| (0.0..=100.0).contains(percent))
+            .ok_or_else(|| {
+                let message = format!("invalid color expression: {expr}");
+                (DiagnosticCode::Unsupported, message)
+            })?;
+        let other = match parts.next() {
+            Some(name) => named(name)?,
+            None => "luma(100%)".to_owned(),
+        };
+        color = format!(
+            "color.mix(({color}, {}%), ({other}, {}%), space: rgb)",
+            number_str(percent),
+            number_str(100.0 - percent)
+        );
+    }
+    Ok(color)
+}
@@ -0,0 +1,241 @@
+//! Parser of the column specification of tabular and array environments,
+//! e.g. `|l|p{3cm}|*{2}{c}|`
+
+use mitex_parser::syntax::SyntaxNode;
+
+use super::length::typst_length;
+use super::ConvertContext;
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// A column of a tabular
+#[derive(Debug, Clone, Default)]
+pub(super) struct Column {
+    /// The Typst alignment, e.g. `left` or `left + horizon`
+    pub align: String,
+    /// The Typst width, `None` for `auto`
+    pub width: Option<String>,
+    /// Whether the cells are bold by `>{\bfseries}`
+    pub strong: bool,
+    /// Whether the cells are italic by `>{\itshape}`
+    pub emph: bool,
+}
+
+/// A parsed column specification
+#[derive(Debug, Clone, Default)]
+pub(super) struct ColSpec {
+    pub columns: Vec<Column>,
+    /// The vertical lines as `(x, count)`, where `count` is 2 for `||`
+    pub vlines: Vec<(usize, usize)>,
+    /// Whether the space between columns stretches by
+    /// `@{\extracolsep{\fill}}`
+    pub fill: bool,
+    /// Warnings about the parts that are not converted
+    pub warnings: Vec<Diagnostic>,
+    /// The length of the specification parsed so far, counting each
+    /// repetition of `*{n}{..}`
+    expanded: usize,
+}
+
+/// The largest count of `*{n}{..}`, above which the specification is
+/// invalid, and of the columns or rows spanned by a cell
+pub(super) const MAX_REPEAT: usize = 256;
+
+/// The largest length of a specification with its repetitions expanded,
+/// which bounds nested repetitions like `*{n}{*{n}{|}}`
+const MAX_EXPANDED: usize = 16 * 1024;
+
+impl ColSpec {
+    /// Parses a column specification starting at `offset` of the input
+    pub fn parse(spec: &str, offset: usize) -> Result<Self, Diagnostic> {
+        let mut res = Self::default();
+        res.parse_into(spec, offset)?;
+        Ok(res)
+    }
+
+    fn parse_into(&mut self, spec: &str, offset: usize) -> Result<(), Diagnostic> {
+        self.expanded += spec.len();
+        let mut chars = spec.char_indices().peekable();
+        // the declarations of `>{..}` applied to the next column
+        let mut pending = Column::default();
+        let mut pending_align = None;
+        while let Some((start, ch)) = chars.next() {
+            let range = offset + start..offset + start + ch.len_utf8();
+            let mut group = |name: char| {
+                let (text, group_offset) = read_group(spec, &mut chars).ok_or_else(|| {
+                    Diagnostic::error(
+                        DiagnosticCode::UnknownAlignment,
+                        format!("missing argument of column specifier: {name}"),
+                        range.clone(),
+                    )
+                })?;
+                Ok::<_, Diagnostic>((text, offset + group_offset))
+            };
+            let (align, width) = match ch {
+                ' ' | '\t' | '\n' | '\r' => continue,
+                'l' => ("left", None),
+                'c' => ("center", None),
+                'r' => ("right", None),
+                // the columns of tabularx taking the remaining width
+                'X' => ("left", Some("1fr".to_owned())),
+                'p' | 'm' | 'b' => {
+                    let (width, width_offset) = group(ch)?;
+                    let typst_width = typst_length(width);
+                    if typst_width.is_none() {
+                        self.warnings.push(Diagnostic::warning(
+                            DiagnosticCode::Unsupported,
+                            format!("unsupported column width: {width}"),
+                            width_offset..width_offset + width.len(),
+                        ));
+                    }
+                    let align = match ch {
+                        'p' => "left",
+                        'm' => "left + horizon",
+                        _ => "left + bottom",
+                    };
+                    (align, typst_width)
+                }
+                '|' => {
+                    let x = self.columns.len();
+                    match self.vlines.last_mut() {
+                        Some((last, count)) if *last == x => *count += 1,
+                        _ => self.vlines.push((x, 1)),
+                    }
+                    continue;
+                }
+                '>' => {
+                    let (decls, decls_offset) = group(ch)?;
+                    for decl in decls.split('\\').map(str::trim) {
+                        match decl {
+                            "" | "arraybackslash" => {}
+                            "bfseries" => pending.strong = true,
+                            "itshape" | "em" => pending.emph = true,
+                            "centering" => pending_align = Some("center"),
+                            "raggedright" => pending_align = Some("left"),
+                            "raggedleft" => pending_align = Some("right"),
+                            _ => self.warnings.push(Diagnostic::warning(
+                                DiagnosticCode::Unsupported,
+                                format!("unsupported column declaration: \\{decl}"),
+                                decls_offset..decls_offset + decls.len(),
+                            )),
+                        }
+                    }
+                    continue;
+                }
+                '<' | '@' | '!' => {
+                    let (text, text_offset) = group(ch)?;
+                    let compact = text.split_whitespace().collect::<String>();
+                    if compact == "\\extracolsep{\\fill}" {
+                        self.fill = true;
+                        continue;
+                    }
+                    let ignored = text
+                        .split('\\')
+                        .map(str::trim)
+                        .any(|part| !matches!(part, "" | "arraybackslash"));
+                    if ignored {
+                        self.warnings.push(Diagnostic::warning(
+                            DiagnosticCode::Unsupported,
+                            format!("ignored column specifier: {ch}{{{text}}}"),
+                            text_offset..text_offset + text.len(),
+                        ));
+                    }
+                    continue;
+                }
+                '*' => {
+                    let (count, _) = group(ch)?;
+                    let (repeated, repeated_offset) = group(ch)?;
+                    let count = count
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|count| *count <= MAX_REPEAT)
+                        .ok_or_else(|| {
+                            Diagnostic::error(
+                                DiagnosticCode::UnknownAlignment,
+                                format!("invalid repeat count: {count}"),
+                                range.clone(),
+                            )
+                        })?;
+                    for _ in 0..count {
+                        self.parse_into(repeated, repeated_offset)?;
+                        if self.expanded > MAX_EXPANDED {
+                            return Err(Diagnostic::error(
+                                DiagnosticCode::UnknownAlignment,
+                                format!("invalid repeat count: {count}"),
+                                range,
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                _ => {
+                    return Err(Diagnostic::error(
+                        DiagnosticCode::UnknownAlignment,
+                        format!("unknown alignment: {ch}"),
+                        range,
+                    ));
+                }
+            };
+            let pending = std::mem::take(&mut pending);
+            self.columns.push(Column {
+                align: pending_align.take().unwrap_or(align).to_owned(),
+                width,
+                ..pending
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether any column has a fixed width
+    pub fn has_widths(&self) -> bool {
+        self.columns.iter().any(|column| column.width.is_some())
+    }
+}
+
+/// Parses the column specification in an argument like `{|l|c|}` or `[r]`,
+/// reporting the parts not converted as warnings
+pub(super) fn parse_colspec(
+    cx: &mut ConvertContext<'_>,
+    arg: &SyntaxNode,
+) -> Result<ColSpec, Diagnostic> {
+    let arg_start: usize = arg.text_range().start().into();
+    let text = arg.text().to_string();
+    // remove { and }, or [ and ] of the alignment like `[r]`
+    let text = text
+        .strip_prefix('{')
+        .and_then(|text| text.strip_suffix('}'))
+        .or_else(|| text.strip_prefix('[')?.strip_suffix(']'))
+        .unwrap_or(&text);
+    let mut spec = ColSpec::parse(text, arg_start + 1)?;
+    for warning in spec.warnings.drain(..) {
+        cx.report(warning);
+    }
+    Ok(spec)
+}
+
+/// Reads a group like `{3cm}` or a single character, returning its content
+/// and the offset of the content
+fn read_group<'a>(
+    spec: &'a str,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'a>>,
+) -> Option<(&'a str, usize)> {
+    while chars.next_if(|(_, ch)| ch.is_whitespace()).is_some() {}
+    let (start, ch) = chars.next()?;
+    if ch != '{' {
+        return Some((&spec[start..start + ch.len_utf8()], start));
+    }
+    let mut depth = 1;
+    for (index, ch) in chars.by_ref() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((&spec[start + 1..index], start + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
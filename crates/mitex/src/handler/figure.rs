@@ -0,0 +1,86 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem};
+use rowan::ast::AstNode;
+
+use super::{env_alias, ConvertContext, EnvHandler};
+use crate::converter::{error_at, ConvertError, LaTeXEnv, LatexSyntaxElem, LatexSyntaxKind};
+use crate::diagnostic::DiagnosticCode;
+
+/// Converts environment `figure` to `#figure`, taking `\caption` as the
+/// caption
+pub struct Figure;
+
+impl EnvHandler for Figure {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let elem: LatexSyntaxElem = env.syntax().clone().into();
+        let typst_name = env_alias(cx.spec, env);
+        let env_kind = LaTeXEnv::Figure;
+        fn is_named_arg(child: &LatexSyntaxElem) -> bool {
+            matches!(
+                child.kind(),
+                LatexSyntaxKind::ItemCmd
+                    if matches!(
+                        CmdItem::cast(child.as_node().unwrap().clone())
+                            .unwrap()
+                            .name_tok()
+                            .unwrap()
+                            .text(),
+                        "\\caption" | "\\centering"
+                    )
+            )
+        }
+        // collect named args
+        let mut caption = None;
+        for child in elem.as_node().unwrap().children_with_tokens() {
+            if is_named_arg(&child) {
+                let cmd = CmdItem::cast(child.as_node().unwrap().clone()).unwrap();
+                let name = cmd.name_tok().unwrap();
+                let name = name.text();
+                // remove prefix \
+                let name = &name[1..];
+                if name == "caption" {
+                    let Some(arg) = cmd.arguments().next() else {
+                        return Err(error_at(
+                            DiagnosticCode::MissingArgument,
+                            "missing text of caption command".to_owned(),
+                            &child,
+                        ));
+                    };
+                    caption = Some(arg);
+                }
+            }
+        }
+        // convert to #figure
+        let prev = cx.conv.enter_env(env_kind);
+        f.write_char('#')?;
+        f.write_str(&typst_name)?;
+        f.write_char('(')?;
+        if let Some(caption) = caption {
+            f.write_str("caption: [")?;
+            cx.convert(f, caption.into())?;
+            f.write_str("],")?;
+        }
+        f.write_str(")[")?;
+        for child in elem.as_node().unwrap().children_with_tokens() {
+            // skip \begin and \end commands
+            if matches!(
+                child.kind(),
+                LatexSyntaxKind::ItemBegin | LatexSyntaxKind::ItemEnd
+            ) || matches!(child.kind(), LatexSyntaxKind::ItemCmd) && is_named_arg(&child)
+            {
+                continue;
+            }
+            cx.convert(f, child)?;
+        }
+        f.write_str("];")?;
+        cx.conv.exit_env(prev);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::length::number_str;
+use super::{CommandHandler, ConvertContext};
+use crate::converter::{write_str_literal, ConvertError, LaTeXMode};
+
+/// The Typst function of the monospace font family like `\texttt`
+pub(crate) const MONO: &str = "#text(font: \"DejaVu Sans Mono\")";
+/// The Typst function of the sans serif font family like `\textsf`
+pub(crate) const SANS: &str = "#text(font: \"New Computer Modern Sans\")";
+
+/// Converts a font command like `\textbf` to the given Typst function in
+/// text mode, and according to the spec in math mode
+pub struct TextFont(pub &'static str);
+
+impl CommandHandler for TextFont {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        cx.conv
+            .convert_normal_command(f, cmd.syntax().clone().into(), cx.spec, Some(self.0))
+    }
+}
+
+/// Converts a declaration like `\bfseries`, `{\it ...}` or `\large` to the
+/// given Typst function wrapping the rest of the enclosing group in text
+/// mode, and according to the spec in math mode
+pub struct Declaration(pub &'static str);
+
+impl CommandHandler for Declaration {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        f.push_str(self.0);
+        write_declared(cx, f, cmd)
+    }
+}
+
+/// Converts a size declaration like `\large` to `#text(size: ..)` wrapping
+/// the rest of the enclosing group in text mode, and according to the spec
+/// in math mode
+///
+/// The size is relative to the base size, so the nested declarations like
+/// `\large a \Huge b` are scaled by the size of the enclosing one.
+pub struct FontSize(pub f64);
+
+impl CommandHandler for FontSize {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let prev = std::mem::replace(&mut cx.conv.font_size, self.0);
+        write!(f, "#text(size: {}em)", number_str(self.0 / prev))?;
+        let res = write_declared(cx, f, cmd);
+        cx.conv.font_size = prev;
+        res
+    }
+}
+
+/// Writes the greedy argument of a declaration as a content block
+fn write_declared(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    cmd: &CmdItem,
+) -> Result<(), ConvertError> {
+    f.push('[');
+    // the greedy argument, without the space after the command name
+    let body = cmd.arguments().flat_map(|arg| arg.children_with_tokens());
+    let body = body.skip_while(|elem| elem.kind() == SyntaxKind::TokenWhiteSpace);
+    for elem in body {
+        cx.convert(f, elem)?;
+    }
+    f.push_str("];");
+    Ok(())
+}
+
+/// Converts a font command like `\mathrm` or `\operatorname` in math mode to
+/// a Typst string, like `upright("d")`, if its argument is plain text
+///
+/// The spaces are ignored as in math mode of LaTeX, while the spacing
+/// commands like `\,` become spaces. Other arguments are converted according
+/// to the spec.
+pub struct MathFont;
+
+impl CommandHandler for MathFont {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let text = match cmd.arguments().next() {
+            Some(arg) if matches!(cx.mode(), LaTeXMode::Math) => plain_text(&arg),
+            _ => None,
+        };
+        let Some(text) = text else {
+            return cx.convert_command(f, cmd);
+        };
+        let name = cmd.name_tok().unwrap();
+        let name = &name.text()[1..];
+        let alias = cx.spec().get_cmd(name).and_then(|cmd| cmd.alias.as_deref());
+        f.push_str(alias.unwrap_or(name));
+        f.push('(');
+        write_str_literal(f, &text)?;
+        f.push(')');
+        Ok(())
+    }
+}
+
+/// The text of a curly group argument like `{arg\,max}`, or `None` if it
+/// contains markup other than words and escaped symbols
+fn plain_text(arg: &SyntaxNode) -> Option<String> {
+    let curly = arg
+        .first_child()
+        .filter(|curly| curly.kind() == SyntaxKind::ItemCurly)?;
+    let mut text = String::new();
+    for elem in curly.descendants_with_tokens() {
+        match elem.kind() {
+            SyntaxKind::ItemCurly
+            | SyntaxKind::ItemText
+            | SyntaxKind::TokenLBrace
+            | SyntaxKind::TokenRBrace
+            | SyntaxKind::ClauseCommandName
+            | SyntaxKind::TokenWhiteSpace
+            | SyntaxKind::TokenLineBreak => {}
+            SyntaxKind::TokenWord
+            | SyntaxKind::TokenComma
+            | SyntaxKind::TokenSlash
+            | SyntaxKind::TokenApostrophe
+            | SyntaxKind::TokenSemicolon
+            | SyntaxKind::TokenAsterisk
+            | SyntaxKind::TokenDitto => text.push_str(elem.as_token().unwrap().text()),
+            SyntaxKind::ItemCmd => {
+                let cmd = CmdItem::cast(elem.into_node().unwrap())?;
+                let escaped = match cmd.name_tok()?.text() {
+                    "\\," | "\\:" | "\\;" | "\\ " => " ",
+                    "\\_" => "_",
+                    "\\#" => "#",
+                    "\\$" => "$",
+                    "\\%" => "%",
+                    "\\&" => "&",
+                    "\\{" => "{",
+                    "\\}" => "}",
+                    "\\textbackslash" => "\\",
+                    _ => return None,
+                };
+                text.push_str(escaped);
+            }
+            _ => return None,
+        }
+    }
+    Some(text)
+}
@@ -0,0 +1,104 @@
+use mitex_parser::syntax::{CmdItem, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::{report_unsupported, CommandHandler, ConvertContext};
+use crate::converter::{ConvertError, LaTeXMode};
+
+/// Converts command `\footnote[number]{text}` to `#footnote[text]`, where the
+/// number is ignored
+pub struct Footnote;
+
+impl CommandHandler for Footnote {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(body) = cmd.arguments().last() else {
+            return cx.convert_command(f, cmd);
+        };
+        write_footnote(cx, f, &body)
+    }
+}
+
+/// Converts command `\footnotemark`, whose text is given later by
+/// `\footnotetext`
+///
+/// Typst places the text of a footnote where its mark is, so the mark is
+/// converted to `#footnote[text]` with the text of the `\footnotetext` paired
+/// with it, i.e. the n-th mark takes the text of the n-th `\footnotetext` in
+/// the document.
+pub struct FootnoteMark;
+
+impl CommandHandler for FootnoteMark {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let (marks, texts) = footnote_commands(cmd);
+        let index = marks.iter().position(|mark| mark.syntax() == cmd.syntax());
+        let text = index.and_then(|index| texts.get(index));
+        let Some(body) = text.and_then(|text| text.arguments().last()) else {
+            report_unsupported(cx, cmd.syntax(), "footnote mark without footnote text");
+            return Ok(());
+        };
+        write_footnote(cx, f, &body)
+    }
+}
+
+/// Converts command `\footnotetext{text}`, which is written at its paired
+/// `\footnotemark`, or converted like `\footnote` if there is no such mark
+pub struct FootnoteText;
+
+impl CommandHandler for FootnoteText {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let (marks, texts) = footnote_commands(cmd);
+        let index = texts.iter().position(|text| text.syntax() == cmd.syntax());
+        if index.is_some_and(|index| index < marks.len()) {
+            return Ok(());
+        }
+        Footnote.convert(cx, f, cmd)
+    }
+}
+
+/// Writes `#footnote[text]`, with the text converted in text mode
+fn write_footnote(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    body: &SyntaxNode,
+) -> Result<(), ConvertError> {
+    let in_text = matches!(cx.mode(), LaTeXMode::Text);
+    f.push_str("#footnote[");
+    let prev = cx.conv.enter_mode(LaTeXMode::Text);
+    let res = cx.convert_arg(f, body);
+    cx.conv.exit_mode(prev);
+    res?;
+    f.push(']');
+    if in_text {
+        f.push(';');
+    }
+    Ok(())
+}
+
+/// All commands `\footnotemark` and `\footnotetext` in the document of the
+/// command, in the order they appear
+fn footnote_commands(cmd: &CmdItem) -> (Vec<CmdItem>, Vec<CmdItem>) {
+    let root = cmd.syntax().ancestors().last().unwrap();
+    let (mut marks, mut texts) = (vec![], vec![]);
+    for cmd in root.descendants().filter_map(CmdItem::cast) {
+        match cmd.name_tok().as_ref().map(|name| name.text()) {
+            Some("\\footnotemark") => marks.push(cmd),
+            Some("\\footnotetext") => texts.push(cmd),
+            _ => {}
+        }
+    }
+    (marks, texts)
+}
@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::CmdItem;
+use rowan::ast::AstNode;
+
+use super::{arg_text, CommandHandler, ConvertContext};
+use crate::converter::{error_at, ConvertError, LatexSyntaxKind};
+use crate::diagnostic::DiagnosticCode;
+
+/// Converts command `\includegraphics[width=0.5\textwidth]{example-image}`
+pub struct IncludeGraphics;
+
+impl CommandHandler for IncludeGraphics {
+    fn convert(
+        &self,
+        _cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let opt_arg = cmd.arguments().find(|arg| {
+            matches!(
+                arg.first_child().unwrap().kind(),
+                LatexSyntaxKind::ItemBracket
+            )
+        });
+        let arg = cmd.arguments().find(|arg| {
+            matches!(
+                arg.first_child().unwrap().kind(),
+                LatexSyntaxKind::ItemCurly
+            )
+        });
+        let Some(arg) = arg else {
+            return Err(error_at(
+                DiagnosticCode::MissingArgument,
+                "missing file of includegraphics command".to_owned(),
+                &cmd.syntax().clone().into(),
+            ));
+        };
+        let body = arg_text(&arg);
+        f.write_str("#image(")?;
+        // optional arguments
+        if let Some(opt_arg) = opt_arg {
+            let arg_text = opt_arg.text().to_string();
+            let arg_text = &arg_text[1..(arg_text.len() - 1)];
+            let arg_text = arg_text.trim();
+            // example: \includegraphics[width=0.5\textwidth, height=3cm,
+            // angle=45]{example-image} split by comma and convert
+            // to key-value pairs
+            let args = arg_text.split(',').collect::<Vec<_>>();
+            let args = args
+                .iter()
+                .map(|arg| {
+                    let arg = arg.trim();
+                    let arg = arg.split('=').collect::<Vec<_>>();
+                    let key = arg[0].trim();
+                    let value = if arg.len() == 2 { arg[1].trim() } else { "" };
+                    (key, value)
+                })
+                .collect::<Vec<_>>();
+            for (key, value) in args.iter() {
+                if matches!(key, &"width" | &"height") {
+                    f.write_str(key)?;
+                    f.write_char(':')?;
+                    f.write_char(' ')?;
+                    if value.ends_with("\\textwidth") {
+                        let value = value.trim_end_matches("\\textwidth");
+                        f.write_str(value)?;
+                        f.write_str(" * 100%")?;
+                    } else if value.ends_with("\\textheight") {
+                        let value = value.trim_end_matches("\\textheight");
+                        f.write_str(value)?;
+                        f.write_str(" * 100%")?;
+                    } else {
+                        f.write_str(value)?;
+                    }
+                    f.write_char(',')?;
+                    f.write_char(' ')?;
+                }
+            }
+        }
+        // image path
+        f.write_char('"')?;
+        f.write_str(&body)?;
+        f.write_char('"')?;
+        f.write_char(')')?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use mitex_parser::syntax::CmdItem;
+
+use super::{CommandHandler, ConvertContext};
+use crate::converter::{ConvertError, LaTeXMode};
+
+/// Converts commands which only configure other commands or the layout, e.g.
+/// `\bibliographystyle` or `\selectlanguage`, to nothing in text mode, and
+/// according to the spec in math mode
+pub struct Silent;
+
+impl CommandHandler for Silent {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        Ok(())
+    }
+}
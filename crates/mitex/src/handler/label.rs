@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::CmdItem;
+use rowan::ast::AstNode;
+
+use super::{arg_text, CommandHandler, ConvertContext};
+use crate::converter::{error_at, write_str_literal, ConvertError, LaTeXEnv, LaTeXMode};
+use crate::diagnostic::DiagnosticCode;
+
+/// Converts command `\label`
+///
+/// The label is written directly in text, and is attached to the enclosing
+/// environment otherwise.
+pub struct Label;
+
+impl CommandHandler for Label {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let conv = &mut *cx.conv;
+        // already written after the heading
+        if conv.attached_label == Some(cmd.syntax().text_range()) {
+            conv.attached_label = None;
+            return Ok(());
+        }
+        let label = label_name(cmd).ok_or_else(|| missing_label(cmd))?;
+        match conv.env {
+            env if matches!(env, LaTeXEnv::None) || env.is_list() => {
+                if matches!(conv.mode, LaTeXMode::Text) && conv.emit_labels {
+                    f.write_char('<')?;
+                    f.write_str(&label)?;
+                    f.write_char('>')?;
+                }
+            }
+            _ => {
+                conv.label = Some(label);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gets the normalized name of a label from command `\label{name}`, or
+/// `None` if the name is missing
+pub(super) fn label_name(cmd: &CmdItem) -> Option<String> {
+    let arg = cmd.arguments().next()?;
+    Some(normalize_label(&arg_text(&arg)))
+}
+
+/// The error of command `\label` without a name
+pub(super) fn missing_label(cmd: &CmdItem) -> ConvertError {
+    error_at(
+        DiagnosticCode::MissingArgument,
+        "missing name of label command".to_owned(),
+        &cmd.syntax().clone().into(),
+    )
+}
+
+/// Normalizes a LaTeX label name to a valid Typst label name
+///
+/// Characters other than letters, digits, `_`, `-`, `.` and `:` are replaced
+/// by `-`, and a trailing `.` or `:` is replaced by `_` since references like
+/// `@fig:` would drop it.
+pub(super) fn normalize_label(label: &str) -> String {
+    let mut label = label
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            '_' | '-' | '.' | ':' => c,
+            _ => '-',
+        })
+        .collect::<String>();
+    if label.ends_with(['.', ':']) {
+        label.pop();
+        label.push('_');
+    }
+    if label.is_empty() {
+        label.push('_');
+    }
+    label
+}
+
+/// Whether a name can be written as a Typst label literal `<name>`
+pub(super) fn is_label_literal(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        && !name.ends_with(['.', ':'])
+}
+
+/// Writes a name as a Typst label, which is a `label` call if the name is
+/// not a valid label literal
+pub(super) fn write_label(f: &mut String, name: &str) -> Result<(), ConvertError> {
+    if is_label_literal(name) {
+        write!(f, "<{name}>")?;
+    } else {
+        f.write_str("label(")?;
+        write_str_literal(f, name)?;
+        f.write_char(')')?;
+    }
+    Ok(())
+}
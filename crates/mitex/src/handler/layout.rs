@@ -0,0 +1,406 @@
+use mitex_parser::syntax::{CmdItem, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::length::{number_str, typst_length};
+use super::{arg_text, report_unsupported, split_options, CommandHandler, ConvertContext};
+use crate::converter::{str_literal, ConvertError, LaTeXMode};
+use crate::document::{class_option_arg, Layout};
+
+/// The languages of babel and polyglossia with their ISO 639 codes and
+/// regions, including the variants like `british`
+const LANGUAGES: &[(&str, &str, Option<&str>)] = &[
+    ("english", "en", None),
+    ("american", "en", Some("us")),
+    ("USenglish", "en", Some("us")),
+    ("british", "en", Some("gb")),
+    ("UKenglish", "en", Some("gb")),
+    ("australian", "en", Some("au")),
+    ("canadian", "en", Some("ca")),
+    ("newzealand", "en", Some("nz")),
+    ("german", "de", None),
+    ("ngerman", "de", None),
+    ("austrian", "de", Some("at")),
+    ("naustrian", "de", Some("at")),
+    ("swissgerman", "de", Some("ch")),
+    ("nswissgerman", "de", Some("ch")),
+    ("french", "fr", None),
+    ("francais", "fr", None),
+    ("acadian", "fr", Some("ca")),
+    ("canadien", "fr", Some("ca")),
+    ("spanish", "es", None),
+    ("mexican", "es", Some("mx")),
+    ("italian", "it", None),
+    ("portuguese", "pt", None),
+    ("portuges", "pt", None),
+    ("brazilian", "pt", Some("br")),
+    ("brazil", "pt", Some("br")),
+    ("dutch", "nl", None),
+    ("danish", "da", None),
+    ("swedish", "sv", None),
+    ("norsk", "nb", None),
+    ("nynorsk", "nn", None),
+    ("finnish", "fi", None),
+    ("icelandic", "is", None),
+    ("polish", "pl", None),
+    ("czech", "cs", None),
+    ("slovak", "sk", None),
+    ("slovene", "sl", None),
+    ("croatian", "hr", None),
+    ("serbian", "sr", None),
+    ("hungarian", "hu", None),
+    ("magyar", "hu", None),
+    ("romanian", "ro", None),
+    ("bulgarian", "bg", None),
+    ("russian", "ru", None),
+    ("ukrainian", "uk", None),
+    ("estonian", "et", None),
+    ("latvian", "lv", None),
+    ("lithuanian", "lt", None),
+    ("greek", "el", None),
+    ("turkish", "tr", None),
+    ("catalan", "ca", None),
+    ("basque", "eu", None),
+    ("galician", "gl", None),
+    ("irish", "ga", None),
+    ("welsh", "cy", None),
+    ("latin", "la", None),
+    ("hebrew", "he", None),
+    ("arabic", "ar", None),
+    ("persian", "fa", None),
+    ("hindi", "hi", None),
+    ("chinese", "zh", None),
+    ("japanese", "ja", None),
+    ("korean", "ko", None),
+    ("vietnamese", "vi", None),
+    ("thai", "th", None),
+    ("indonesian", "id", None),
+];
+
+/// The factors of the line spacing of setspace, for the 10pt font size
+const SPACINGS: &[(&str, f64)] = &[
+    ("\\singlespacing", 1.0),
+    ("\\onehalfspacing", 1.25),
+    ("\\doublespacing", 1.667),
+];
+
+/// Records the layout set by the options of `\usepackage[options]{packages}`
+/// for geometry, babel and setspace, without output
+pub struct UsePackage;
+
+impl CommandHandler for UsePackage {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let mut options = None;
+        let mut packages = None;
+        for arg in cmd.arguments() {
+            match arg.first_child().map(|child| child.kind()) {
+                Some(SyntaxKind::ItemBracket) => options = Some(arg),
+                _ => packages = Some(arg),
+            }
+        }
+        let (Some(options), Some(packages)) = (options, packages) else {
+            return Ok(());
+        };
+        for package in arg_text(&packages).split(',').map(str::trim) {
+            match package {
+                "geometry" => set_geometry(cx, &options),
+                "babel" => {
+                    let text = arg_text(&options);
+                    let babel_options = split_options(&text);
+                    // the main language is the last one unless given by `main`
+                    let main = babel_options
+                        .iter()
+                        .find_map(|(key, value)| value.filter(|_| *key == "main"));
+                    let mut languages = babel_options.iter().filter(|(_, value)| value.is_none());
+                    if let Some(main) = main.or(languages.next_back().map(|(key, _)| *key)) {
+                        set_language(cx, &options, main);
+                    }
+                }
+                "setspace" => {
+                    for (option, _) in split_options(&arg_text(&options)) {
+                        let spacing = SPACINGS.iter().find(|(name, _)| name[1..] == *option);
+                        if let Some((_, factor)) = spacing {
+                            cx.conv.metadata.layout.set_line_spread(*factor);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the page size and the margins of `\geometry{options}`, without
+/// output
+pub struct Geometry;
+
+impl CommandHandler for Geometry {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if let Some(options) = cmd.arguments().last() {
+            set_geometry(cx, &options);
+        }
+        Ok(())
+    }
+}
+
+/// Records the paragraph indent and spacing of `\setlength{\parindent}{..}`
+/// and `\setlength{\parskip}{..}`, without output
+pub struct SetLength;
+
+impl CommandHandler for SetLength {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let args = cmd.arguments().collect::<Vec<_>>();
+        let [register, value] = &args[..] else {
+            return Ok(());
+        };
+        let register = arg_text(register);
+        if !matches!(register.as_str(), "\\parindent" | "\\parskip") {
+            report_unsupported(cx, cmd.syntax(), format!("unsupported length: {register}"));
+            return Ok(());
+        }
+        // the stretch of `\parskip` like `1em plus 1pt` is dropped
+        let text = arg_text(value);
+        let length = text.split(" plus").next().unwrap_or_default();
+        match typst_length(length).filter(|length| !length.ends_with('%')) {
+            Some(length) if register == "\\parskip" => {
+                cx.conv.metadata.layout.par_spacing = Some(length);
+            }
+            Some(length) => {
+                let par = &mut cx.conv.metadata.layout.par;
+                par.insert("first-line-indent".to_owned(), length);
+            }
+            None => report_unsupported(cx, value, format!("unsupported length: {text}")),
+        }
+        Ok(())
+    }
+}
+
+/// Records the line spacing of `\linespread{factor}`, `\setstretch{factor}`
+/// or `\onehalfspacing` etc. of setspace, without output
+pub struct LineSpread;
+
+impl CommandHandler for LineSpread {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        let name = cmd.name_tok().unwrap();
+        if let Some((_, factor)) = SPACINGS.iter().find(|(n, _)| *n == name.text()) {
+            cx.conv.metadata.layout.set_line_spread(*factor);
+            return Ok(());
+        }
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        match arg_text(&arg).parse::<f64>() {
+            Ok(factor) if factor > 0.0 => cx.conv.metadata.layout.set_line_spread(factor),
+            _ => {
+                let message = format!("unsupported line spread: {}", arg_text(&arg));
+                report_unsupported(cx, &arg, message);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records whether the pages are numbered by `\pagestyle{style}`, without
+/// output
+pub struct PageStyle;
+
+impl CommandHandler for PageStyle {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        let style = arg_text(&arg);
+        match style.as_str() {
+            "empty" | "plain" | "headings" | "myheadings" | "fancy" => {
+                cx.conv.metadata.layout.page_style = Some(style);
+            }
+            _ => report_unsupported(cx, &arg, format!("unsupported page style: {style}")),
+        }
+        Ok(())
+    }
+}
+
+/// Records the numbering of the pages of `\pagenumbering{style}`, without
+/// output
+pub struct PageNumbering;
+
+impl CommandHandler for PageNumbering {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        let style = arg_text(&arg);
+        let numbering = match style.as_str() {
+            "arabic" => r#""1""#,
+            "roman" => r#""i""#,
+            "Roman" => r#""I""#,
+            "alph" => r#""a""#,
+            "Alph" => r#""A""#,
+            "gobble" => "none",
+            _ => {
+                report_unsupported(cx, &arg, format!("unsupported page numbering: {style}"));
+                return Ok(());
+            }
+        };
+        cx.conv.metadata.layout.numbering = Some(numbering.to_owned());
+        Ok(())
+    }
+}
+
+/// Records the language of `\setmainlanguage[variant=british]{english}` of
+/// polyglossia, without output
+pub struct MainLanguage;
+
+impl CommandHandler for MainLanguage {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let mut variant = None;
+        let mut language = None;
+        for arg in cmd.arguments() {
+            match arg.first_child().map(|child| child.kind()) {
+                Some(SyntaxKind::ItemBracket) => {
+                    let text = arg_text(&arg);
+                    variant = split_options(&text)
+                        .into_iter()
+                        .find_map(|(key, value)| value.filter(|_| key == "variant"))
+                        .map(str::to_owned);
+                }
+                _ => language = Some(arg),
+            }
+        }
+        let Some(language) = language else {
+            return Ok(());
+        };
+        let name = arg_text(&language);
+        // the variant is looked up first, e.g. `british` for `english`
+        let known = |name: &str| LANGUAGES.iter().any(|(n, ..)| *n == name);
+        let name = variant.filter(|variant| known(variant)).unwrap_or(name);
+        set_language(cx, &language, &name);
+        Ok(())
+    }
+}
+
+impl Layout {
+    /// Sets the leading for the line spacing scaled by `factor`, where the
+    /// default leading `0.65em` makes the distance between the baselines
+    /// about `1.3em`
+    fn set_line_spread(&mut self, factor: f64) {
+        let leading = number_str(1.3 * factor - 0.65);
+        self.par
+            .insert("leading".to_owned(), format!("{leading}em"));
+    }
+}
+
+/// Records the options of geometry like `margin=1in` and `a4paper`
+fn set_geometry(cx: &mut ConvertContext<'_>, options: &SyntaxNode) {
+    let text = arg_text(options);
+    for (key, value) in split_options(&text) {
+        let Some(value) = value else {
+            match class_option_arg(key) {
+                Some((key @ ("paper" | "flipped"), value)) => {
+                    cx.conv.metadata.layout.page.insert(key.to_owned(), value);
+                }
+                _ if key == "portrait" => {
+                    let page = &mut cx.conv.metadata.layout.page;
+                    page.insert("flipped".to_owned(), "false".to_owned());
+                }
+                _ => report_unsupported(cx, options, format!("unsupported geometry option: {key}")),
+            }
+            continue;
+        };
+        let side = match key {
+            "margin" => "rest",
+            "left" | "lmargin" => "left",
+            "right" | "rmargin" => "right",
+            "top" | "tmargin" => "top",
+            "bottom" | "bmargin" => "bottom",
+            "inner" => "inside",
+            "outer" => "outside",
+            "hmargin" => "x",
+            "vmargin" => "y",
+            "paper" | "papername" => {
+                match class_option_arg(value).filter(|(key, _)| *key == "paper") {
+                    Some((key, value)) => {
+                        cx.conv.metadata.layout.page.insert(key.to_owned(), value);
+                    }
+                    None => report_unsupported(cx, options, format!("unsupported paper: {value}")),
+                }
+                continue;
+            }
+            _ => {
+                report_unsupported(cx, options, format!("unsupported geometry option: {key}"));
+                continue;
+            }
+        };
+        let Some(length) = typst_length(value) else {
+            report_unsupported(cx, options, format!("unsupported length: {value}"));
+            continue;
+        };
+        let margin = &mut cx.conv.metadata.layout.margin;
+        // a later margin overrides the earlier ones of the same sides
+        match side {
+            "rest" => margin.clear(),
+            "x" => margin.retain(|side, _| !matches!(side.as_str(), "left" | "right")),
+            "y" => margin.retain(|side, _| !matches!(side.as_str(), "top" | "bottom")),
+            _ => {}
+        }
+        margin.insert(side.to_owned(), length);
+    }
+}
+
+/// Records the language of the text by its name in babel or polyglossia
+fn set_language(cx: &mut ConvertContext<'_>, arg: &SyntaxNode, name: &str) {
+    let Some((_, lang, region)) = LANGUAGES.iter().find(|(n, ..)| *n == name) else {
+        report_unsupported(cx, arg, format!("unsupported language: {name}"));
+        return;
+    };
+    let text = &mut cx.conv.metadata.layout.text;
+    text.insert("lang".to_owned(), str_literal(lang));
+    match region {
+        Some(region) => text.insert("region".to_owned(), str_literal(region)),
+        None => text.remove("region"),
+    };
+}
@@ -0,0 +1,60 @@
+/// Converts a LaTeX length like `3cm` or `0.5\textwidth` to a Typst length
+///
+/// Lengths relative to the text width become ratios. Returns `None` if the
+/// length has an unknown unit or refers to other registers.
+pub(crate) fn typst_length(length: &str) -> Option<String> {
+    let length = length.trim();
+    let split = length
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | ' ')))
+        .unwrap_or(length.len());
+    let (number, unit) = length.split_at(split);
+    let number = number.replace(' ', "");
+    let unit = unit.trim();
+    let value = match number.as_str() {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        number => number.parse::<f64>().ok()?,
+    };
+    Some(match unit {
+        "mm" | "cm" | "in" | "em" => format!("{}{unit}", number_str(value)),
+        // the points of Typst are the big points of TeX, 72 per inch
+        "bp" => format!("{}pt", number_str(value)),
+        "pt" => format!("{}pt", number_str(value * 72.0 / 72.27)),
+        "pc" => format!("{}pt", number_str(value * 12.0 * 72.0 / 72.27)),
+        "ex" => format!("{}em", number_str(value / 2.0)),
+        "\\textwidth" | "\\linewidth" | "\\columnwidth" | "\\hsize" => {
+            format!("{}%", number_str(value * 100.0))
+        }
+        _ => return None,
+    })
+}
+
+/// Formats a number without trailing zeros
+pub(super) fn number_str(value: f64) -> String {
+    let value = (value * 1000.0).round() / 1000.0;
+    format!("{value}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::typst_length;
+
+    #[test]
+    fn points() {
+        assert_eq!(typst_length("72.27pt").as_deref(), Some("72pt"));
+        assert_eq!(typst_length("10pt").as_deref(), Some("9.963pt"));
+        assert_eq!(typst_length("-2 pt").as_deref(), Some("-1.993pt"));
+    }
+
+    #[test]
+    fn big_points() {
+        assert_eq!(typst_length("72bp").as_deref(), Some("72pt"));
+        assert_eq!(typst_length("0.5bp").as_deref(), Some("0.5pt"));
+    }
+
+    #[test]
+    fn picas() {
+        assert_eq!(typst_length("1pc").as_deref(), Some("11.955pt"));
+        assert_eq!(typst_length("6.0225pc").as_deref(), Some("72pt"));
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, SyntaxKind, SyntaxNode};
+
+use super::label::{is_label_literal, write_label};
+use super::verbatim::verbatim_arg;
+use super::{arg_text, CommandHandler, ConvertContext};
+use crate::converter::{write_str_literal, ConvertError, LaTeXMode};
+
+/// Converts command `\href{url}{text}` to `#link("url")[text]`, and
+/// `\url{url}` to `#link("url")`
+///
+/// The url is read raw, so `%`, `#` and `_` in it are kept. The escapes like
+/// `\#` and `\%` accepted by `\href` are unescaped.
+pub struct Href;
+
+impl CommandHandler for Href {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let name = cmd.name_tok().unwrap();
+        let url = verbatim_arg(cmd);
+        let url = match name.text() {
+            "\\href" => unescape_url(&url),
+            _ => url,
+        };
+        f.write_str("#link(")?;
+        write_str_literal(f, &url)?;
+        f.write_char(')')?;
+        if let Some(body) = text_arg(cmd) {
+            write_body(cx, f, &body)?;
+        }
+        if matches!(cx.mode(), LaTeXMode::Text) {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\nolinkurl{url}` to the url as a string
+pub struct NoLinkUrl;
+
+impl CommandHandler for NoLinkUrl {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        f.write_char('#')?;
+        write_str_literal(f, &verbatim_arg(cmd))?;
+        if matches!(cx.mode(), LaTeXMode::Text) {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\hyperlink{name}{text}` to `#link(<name>)[text]`
+///
+/// The text is written alone if labels are not emitted, since the target
+/// would be missing.
+pub struct HyperLink;
+
+impl CommandHandler for HyperLink {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let mut args = cmd.arguments();
+        let (Some(name), Some(body)) = (args.next(), args.next()) else {
+            return cx.convert_command(f, cmd);
+        };
+        if !cx.conv.emit_labels {
+            return write_text(cx, f, &body);
+        }
+        f.write_str("#link(")?;
+        write_label(f, &arg_text(&name))?;
+        f.write_char(')')?;
+        write_body(cx, f, &body)?;
+        if matches!(cx.mode(), LaTeXMode::Text) {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\hypertarget{name}{text}` to `#[text]<name>`, the target
+/// of `\hyperlink`
+pub struct HyperTarget;
+
+impl CommandHandler for HyperTarget {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let mut args = cmd.arguments();
+        let (Some(name), Some(body)) = (args.next(), args.next()) else {
+            return cx.convert_command(f, cmd);
+        };
+        // labels are only attached in text
+        if !cx.conv.emit_labels || matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_arg(f, &body);
+        }
+        let name = arg_text(&name);
+        f.write_char('#')?;
+        write_body(cx, f, &body)?;
+        if is_label_literal(&name) {
+            write!(f, "<{name}>")?;
+        } else {
+            // names like `a b` need the label function
+            f.write_char('#')?;
+            write_label(f, &name)?;
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// The argument of the text after the url, if any
+fn text_arg(cmd: &CmdItem) -> Option<SyntaxNode> {
+    cmd.arguments().find(|arg| {
+        arg.children_with_tokens()
+            .all(|child| child.kind() != SyntaxKind::TokenVerbatim)
+    })
+}
+
+/// Writes an argument as a content block `[text]`, in text mode
+fn write_body(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    body: &SyntaxNode,
+) -> Result<(), ConvertError> {
+    f.write_char('[')?;
+    let prev = cx.conv.enter_mode(LaTeXMode::Text);
+    let res = cx.convert_arg(f, body);
+    cx.conv.exit_mode(prev);
+    res?;
+    f.write_char(']')?;
+    Ok(())
+}
+
+/// Writes an argument as text in text mode, and as `#[text]` in math mode
+fn write_text(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    body: &SyntaxNode,
+) -> Result<(), ConvertError> {
+    if matches!(cx.mode(), LaTeXMode::Math) {
+        f.write_char('#')?;
+        return write_body(cx, f, body);
+    }
+    cx.convert_arg(f, body)
+}
+
+/// Removes the backslashes escaping the special characters of TeX in a url
+fn unescape_url(url: &str) -> String {
+    let mut unescaped = String::with_capacity(url.len());
+    let mut chars = url.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.next_if(|next| c == '\\' && "#%&_~$".contains(*next)) {
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push(c),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,283 @@
+use std::fmt::Write;
+
+use mitex_parser::spec::ContextFeature;
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::{
+    arg_text, report_unsupported, split_options, CommandHandler, ConvertContext, EnvHandler,
+};
+use crate::converter::{error_at, write_str_literal, ConvertError, Converter, LaTeXEnv, LaTeXMode};
+use crate::diagnostic::DiagnosticCode;
+
+/// Converts environment `itemize`, `enumerate` and `description` to markup
+/// lists, with the options of enumitem like `[label=(\alph*), start=3]`
+///
+/// An item with a custom label like `\item[$\star$]` in itemize or enumerate
+/// becomes a list of its own with the label as the marker, while the items
+/// of enumerate after it keep their numbers.
+pub struct List;
+
+impl EnvHandler for List {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let name = env.name_tok().unwrap();
+        let kind = match cx.spec().get_env(name.text().trim()) {
+            Some(shape) => match shape.ctx_feature {
+                ContextFeature::IsEnumerate => LaTeXEnv::Enumerate,
+                ContextFeature::IsDescription => LaTeXEnv::Description,
+                _ => LaTeXEnv::Itemize,
+            },
+            None => LaTeXEnv::Itemize,
+        };
+        let options = match env.arguments().next() {
+            Some(arg) => parse_options(cx, kind, &arg)?,
+            None => ListOptions::default(),
+        };
+
+        // the options apply to the list and the nested lists
+        let rule = match (kind, &options.numbering, &options.marker) {
+            (LaTeXEnv::Enumerate, Some(numbering), _) => {
+                let mut rule = String::from("#set enum(numbering: ");
+                write_str_literal(&mut rule, numbering)?;
+                rule.push(')');
+                Some(rule)
+            }
+            (LaTeXEnv::Itemize, _, Some(marker)) => Some(format!("#set list(marker: [{marker}])")),
+            _ => None,
+        };
+        if let Some(rule) = &rule {
+            write!(f, "#[{rule}")?;
+        }
+
+        let prev = cx.conv.enter_env(kind);
+        let res: Result<(), ConvertError> = (|| {
+            let mut number = options.start.unwrap_or(1);
+            // whether the number of the next item must be written, i.e. at
+            // the start or after an item with a custom label
+            let mut explicit = options.start.is_some();
+            let mut in_custom = false;
+            let mut after_item = false;
+            for child in env.syntax().children_with_tokens() {
+                if matches!(child.kind(), SyntaxKind::ItemBegin | SyntaxKind::ItemEnd) {
+                    continue;
+                }
+                if std::mem::take(&mut after_item) && child.kind() == SyntaxKind::TokenWhiteSpace {
+                    continue;
+                }
+                let item = child
+                    .as_node()
+                    .cloned()
+                    .and_then(CmdItem::cast)
+                    .filter(|cmd| cmd.name_tok().is_some_and(|name| name.text() == "\\item"));
+                let Some(item) = item else {
+                    cx.convert(f, child)?;
+                    continue;
+                };
+
+                if std::mem::take(&mut in_custom) {
+                    f.write_str("];")?;
+                }
+                let label = item_label(cx, &item)?;
+                write_item_start(cx.conv, f)?;
+                match (kind, label) {
+                    (LaTeXEnv::Description, label) => {
+                        write!(f, "/ {}: ", label.unwrap_or_default())?;
+                    }
+                    (_, Some(label)) => {
+                        write!(f, "#list(marker: [{label}])[")?;
+                        in_custom = true;
+                        explicit = true;
+                    }
+                    (LaTeXEnv::Itemize, None) => f.write_str("- ")?,
+                    (_, None) => {
+                        if std::mem::take(&mut explicit) {
+                            write!(f, "{number}. ")?;
+                        } else {
+                            f.write_str("+ ")?;
+                        }
+                        number += 1;
+                    }
+                }
+                after_item = true;
+            }
+            if in_custom {
+                f.write_str("];")?;
+            }
+            Ok(())
+        })();
+        cx.conv.exit_env(prev);
+        res?;
+
+        if rule.is_some() {
+            f.write_char(']')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\item` for lists, which is only reached if the item is
+/// not directly in the list, e.g. in a group
+pub struct Item;
+
+impl CommandHandler for Item {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if !cx.conv.env.is_list() {
+            Err(error_at(
+                DiagnosticCode::ItemOutsideList,
+                "item command outside of itemize, enumerate or description".to_owned(),
+                &cmd.syntax().clone().into(),
+            ))?;
+        }
+        let label = item_label(cx, cmd)?;
+        write_item_start(cx.conv, f)?;
+        match cx.conv.env {
+            LaTeXEnv::Description => write!(f, "/ {}: ", label.unwrap_or_default())?,
+            env => {
+                if label.is_some() {
+                    let message = "ignored label of item not directly in the list";
+                    report_unsupported(cx, cmd.syntax(), message);
+                }
+                match env {
+                    LaTeXEnv::Itemize => f.write_str("- ")?,
+                    _ => f.write_str("+ ")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The options of enumitem which can be converted
+#[derive(Default)]
+struct ListOptions {
+    /// The numbering pattern of enumerate, like `"(a)"`
+    numbering: Option<String>,
+    /// The marker of itemize, converted to Typst markup
+    marker: Option<String>,
+    /// The number of the first item of enumerate
+    start: Option<i64>,
+}
+
+/// Parses the options like `[label=(\alph*), start=3, noitemsep]`, or the
+/// short labels like `[(a)]`, reporting the ones cannot be converted
+fn parse_options(
+    cx: &mut ConvertContext<'_>,
+    kind: LaTeXEnv,
+    arg: &SyntaxNode,
+) -> Result<ListOptions, ConvertError> {
+    let mut options = ListOptions::default();
+    let text = arg_text(arg);
+    for (key, raw_value) in split_options(&text) {
+        let value = raw_value.map(strip_braces);
+        let label = match (key, value) {
+            ("label", Some(value)) => Some(value),
+            ("start", Some(value)) => match value.parse() {
+                Ok(start) if matches!(kind, LaTeXEnv::Enumerate) => {
+                    options.start = Some(start);
+                    continue;
+                }
+                _ => None,
+            },
+            // the items converted are always tight
+            ("noitemsep" | "nosep" | "nolistsep", None) => continue,
+            // a short label like `(a)` or `--`, but not a flag like `wide`
+            (key, None) if !key.bytes().all(|c| c.is_ascii_alphabetic()) => Some(key),
+            _ => None,
+        };
+        let converted = match (kind, label) {
+            (LaTeXEnv::Enumerate, Some(label)) => numbering_pattern(label).map(|pattern| {
+                options.numbering = Some(pattern);
+            }),
+            (LaTeXEnv::Itemize, Some(label)) => {
+                let root = mitex_parser::parse(label, cx.spec().clone());
+                let mut marker = String::new();
+                cx.convert_in(&mut marker, root.into(), LaTeXMode::Text)?;
+                options.marker = Some(marker.trim().to_owned());
+                Some(())
+            }
+            _ => None,
+        };
+        if converted.is_none() {
+            let option = match raw_value {
+                Some(value) => format!("{key}={value}"),
+                None => key.to_owned(),
+            };
+            report_unsupported(cx, arg, format!("unsupported list option: {option}"));
+        }
+    }
+    Ok(options)
+}
+
+/// Converts a label of enumerate like `(\alph*)` or the short label `(a)` to
+/// the numbering pattern of Typst
+fn numbering_pattern(label: &str) -> Option<String> {
+    const COUNTERS: &[(&str, char)] = &[
+        ("\\arabic*", '1'),
+        ("\\alph*", 'a'),
+        ("\\Alph*", 'A'),
+        ("\\roman*", 'i'),
+        ("\\Roman*", 'I'),
+    ];
+    let mut pattern = String::new();
+    let mut counters = 0;
+    let mut rest = label;
+    while let Some(c) = rest.chars().next() {
+        if let Some((command, counter)) = COUNTERS.iter().find(|(cmd, _)| rest.starts_with(cmd)) {
+            pattern.push(*counter);
+            counters += 1;
+            rest = &rest[command.len()..];
+            continue;
+        }
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '{' | '}' => {}
+            // the counters of the short labels
+            '1' | 'a' | 'A' | 'i' | 'I' => {
+                pattern.push(c);
+                counters += 1;
+            }
+            // other letters would be taken as counters, and commands are not
+            // supported
+            c if c.is_alphanumeric() || c == '\\' => return None,
+            c => pattern.push(c),
+        }
+    }
+    (counters == 1).then_some(pattern)
+}
+
+/// Converts the label of `\item[label]`, if any
+fn item_label(cx: &mut ConvertContext<'_>, cmd: &CmdItem) -> Result<Option<String>, ConvertError> {
+    let Some(arg) = cmd.arguments().next() else {
+        return Ok(None);
+    };
+    let mut label = String::new();
+    cx.convert_arg(&mut label, &arg)?;
+    Ok(Some(label.trim().to_owned()))
+}
+
+/// Starts an item on a new line, indented by the depth of the list
+fn write_item_start(conv: &mut Converter, f: &mut String) -> Result<(), ConvertError> {
+    f.write_char('\n')?;
+    for _ in 0..(conv.indent - 2) {
+        f.write_char(' ')?;
+    }
+    Ok(())
+}
+
+/// The value without a pair of surrounding braces like `{(\alph*)}`
+fn strip_braces(value: &str) -> &str {
+    value
+        .strip_prefix('{')
+        .and_then(|value| value.strip_suffix('}'))
+        .unwrap_or(value)
+}
@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxKind};
+use rowan::ast::AstNode;
+
+use super::colspec::{parse_colspec, ColSpec};
+use super::{report_unsupported, ConvertContext, EnvHandler};
+use crate::converter::{ConvertError, LaTeXEnv, LaTeXMode};
+use crate::diagnostic::{Diagnostic, DiagnosticCode, Severity};
+
+/// Converts environment `array` and the starred matrices of mathtools like
+/// `pmatrix*` to `mat`
+///
+/// The column specification of `array`, or the alignment like `[r]` of the
+/// starred matrices, aligns the columns by alignment points in the cells.
+/// The vertical rules of the specification and `\hline` between rows become
+/// the augmentation lines.
+pub struct Matrix;
+
+impl EnvHandler for Matrix {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let name = env.name_tok().unwrap();
+        let name = name.text().trim();
+        let starred = name.ends_with('*');
+        let delim = match name.trim_end_matches('*') {
+            "pmatrix" => "\"(\"",
+            "bmatrix" => "\"[\"",
+            "Bmatrix" => "\"{\"",
+            "vmatrix" => "\"|\"",
+            "Vmatrix" => "\"||\"",
+            _ => "#none",
+        };
+        let spec = match env.arguments().next() {
+            // still converted with centered columns
+            Some(arg) => parse_colspec(cx, &arg).unwrap_or_else(|diag| {
+                cx.report(Diagnostic {
+                    severity: Severity::Warning,
+                    ..diag
+                });
+                ColSpec::default()
+            }),
+            None => ColSpec::default(),
+        };
+        // the columns of the starred matrices all follow the alignment
+        let align = |column: usize| {
+            let column = if starred {
+                spec.columns.first()
+            } else {
+                spec.columns.get(column)
+            };
+            column.map_or("center", |column| column.align.as_str())
+        };
+
+        let mut rows = vec![vec![String::new()]];
+        let mut hlines = vec![];
+        let with_dollar = matches!(cx.mode(), LaTeXMode::Text);
+        let prev_env = cx.conv.enter_env(LaTeXEnv::Matrix);
+        let prev_mode = cx.conv.enter_mode(LaTeXMode::Math);
+        let res: Result<(), ConvertError> = (|| {
+            let mut after_newline = false;
+            let mut in_spacing = false;
+            for child in env.syntax().children_with_tokens() {
+                // skip the spacing of `\\[2pt]`
+                if std::mem::take(&mut after_newline) && child.kind() == SyntaxKind::TokenLBracket {
+                    in_spacing = true;
+                }
+                if in_spacing {
+                    in_spacing = child.kind() != SyntaxKind::TokenRBracket;
+                    continue;
+                }
+                let row = rows.last_mut().unwrap();
+                match child.kind() {
+                    SyntaxKind::ItemBegin | SyntaxKind::ItemEnd => {}
+                    SyntaxKind::TokenAmpersand => row.push(String::new()),
+                    SyntaxKind::ItemNewLine => {
+                        rows.push(vec![String::new()]);
+                        after_newline = true;
+                    }
+                    _ => {
+                        let at_start = row.len() == 1 && row[0].trim().is_empty();
+                        let is_hline = child
+                            .as_node()
+                            .cloned()
+                            .and_then(CmdItem::cast)
+                            .and_then(|cmd| cmd.name_tok())
+                            .is_some_and(|name| name.text() == "\\hline");
+                        if at_start && is_hline {
+                            let range = child.text_range();
+                            hlines.push((rows.len() - 1, range.start().into()..range.end().into()));
+                            continue;
+                        }
+                        cx.convert(row.last_mut().unwrap(), child)?;
+                    }
+                }
+            }
+            Ok(())
+        })();
+        cx.conv.exit_mode(prev_mode);
+        cx.conv.exit_env(prev_env);
+        res?;
+        // the empty row after the last `\\`
+        if rows.len() > 1
+            && rows
+                .last()
+                .unwrap()
+                .iter()
+                .all(|cell| cell.trim().is_empty())
+        {
+            rows.pop();
+        }
+
+        // augment: #(hline: 1, vline: (1, 2)),
+        let mut augment = vec![];
+        let vlines = spec.vlines.iter().map(|&(x, _)| x);
+        let offsets = vlines
+            .filter(|&x| x > 0 && x < spec.columns.len())
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        if offsets.len() < spec.vlines.len() {
+            let arg = env.arguments().next().unwrap();
+            report_unsupported(cx, &arg, "ignored rule at the border of matrix");
+        }
+        if !offsets.is_empty() {
+            augment.push(format!("vline: {}", offsets_str(&offsets)));
+        }
+
+        let mut offsets = vec![];
+        for (row, range) in hlines {
+            if row == 0 || row >= rows.len() {
+                cx.report(Diagnostic::warning(
+                    DiagnosticCode::Unsupported,
+                    "ignored rule at the border of matrix",
+                    range,
+                ));
+            } else {
+                offsets.push(row.to_string());
+            }
+        }
+        if !offsets.is_empty() {
+            augment.insert(0, format!("hline: {}", offsets_str(&offsets)));
+        }
+
+        if with_dollar {
+            f.write_str("$ ")?;
+        }
+        write!(f, "mat(delim: {delim}, ")?;
+        if !augment.is_empty() {
+            write!(f, "augment: #({}), ", augment.join(", "))?;
+        }
+        for (index, row) in rows.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            for (column, cell) in row.iter().enumerate() {
+                if column > 0 {
+                    f.write_str(", ")?;
+                }
+                let cell = match cell.trim() {
+                    "" => "zws",
+                    cell => cell,
+                };
+                // align cells by the alignment points at their sides
+                match align(column) {
+                    align if align.starts_with("left") => write!(f, "& {cell}")?,
+                    "right" => write!(f, "{cell} &")?,
+                    _ => f.write_str(cell)?,
+                }
+            }
+        }
+        f.write_char(')')?;
+        if with_dollar {
+            f.write_str(" $")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats offsets like `1` or `(1, 2)`
+fn offsets_str(offsets: &[String]) -> String {
+    match offsets {
+        [offset] => offset.clone(),
+        offsets => format!("({})", offsets.join(", ")),
+    }
+}
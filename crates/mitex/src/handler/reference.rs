@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::CmdItem;
+use rowan::ast::AstNode;
+
+use super::label::normalize_label;
+use super::{CommandHandler, ConvertContext};
+use crate::converter::{error_at, ConvertError};
+use crate::diagnostic::DiagnosticCode;
+
+/// The kind of a reference command
+#[derive(Debug, Clone, Copy)]
+pub enum RefKind {
+    /// `\ref`, the number only
+    Number,
+    /// `\eqref`, the number in parentheses
+    Equation,
+    /// `\autoref` and `\cref`, the number with a supplement like "Figure"
+    Supplement,
+    /// `\pageref`, the page number
+    Page,
+}
+
+/// Converts reference commands like `\ref{label}` to Typst references
+///
+/// The argument is a comma-separated list of labels, which are joined like
+/// "1, 2 and 3".
+pub struct Reference(pub RefKind);
+
+impl CommandHandler for Reference {
+    fn convert(
+        &self,
+        _cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Err(error_at(
+                DiagnosticCode::MissingArgument,
+                "missing label of reference command".to_owned(),
+                &cmd.syntax().clone().into(),
+            ));
+        };
+        // remove { and }
+        let arg = arg.text().to_string();
+        let arg = arg.trim_start_matches('{').trim_end_matches('}');
+        let labels = arg
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(normalize_label)
+            .collect::<Vec<_>>();
+        for (index, label) in labels.iter().enumerate() {
+            if index > 0 {
+                f.write_str(if index + 1 == labels.len() {
+                    " and "
+                } else {
+                    ", "
+                })?;
+            }
+            match self.0 {
+                RefKind::Number => write!(f, "#ref(<{label}>, supplement: none)")?,
+                RefKind::Equation => write!(f, "(#ref(<{label}>, supplement: none))")?,
+                RefKind::Supplement => write!(f, "#ref(<{label}>)")?,
+                RefKind::Page => write!(f, "#context counter(page).at(<{label}>).first()")?,
+            }
+        }
+        // end the last call in case of a following `[` or `(`
+        if !labels.is_empty() && !matches!(self.0, RefKind::Equation) {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::label::label_name;
+use super::{CommandHandler, ConvertContext};
+use crate::converter::{ConvertError, LaTeXMode};
+
+/// The depth of the sectioning commands, following LaTeX
+pub(super) fn depth(name: &str) -> Option<i8> {
+    Some(match name.trim_end_matches('*') {
+        "part" => -1,
+        "chapter" => 0,
+        "section" => 1,
+        "subsection" => 2,
+        "subsubsection" => 3,
+        "paragraph" => 4,
+        "subparagraph" => 5,
+        _ => return None,
+    })
+}
+
+/// Finds the depth of the top level heading in the document, so that
+/// `\section` is a level 1 heading in an article but a level 2 heading in a
+/// book
+fn heading_top(root: &SyntaxNode) -> i8 {
+    root.descendants()
+        .filter_map(CmdItem::cast)
+        .filter_map(|cmd| depth(&cmd.name_tok()?.text()[1..]))
+        .filter(|depth| *depth <= 1)
+        .min()
+        .unwrap_or(1)
+}
+
+/// The level of the heading converted from a sectioning command of the depth
+/// in the document of the command
+pub(super) fn heading_level(cx: &mut ConvertContext<'_>, cmd: &CmdItem, depth: i8) -> i8 {
+    let top = *cx.conv.heading_top.get_or_insert_with(|| {
+        let root = cmd.syntax().ancestors().last().unwrap();
+        heading_top(&root)
+    });
+    (depth - top + 1).max(1)
+}
+
+/// Converts sectioning commands like `\section[short]{title}` to headings
+///
+/// Starred commands are neither numbered nor outlined, the short title is
+/// shown in the outline, and a `\label` following the command is attached to
+/// the heading. The numbering is set like `1.1` before the first heading,
+/// unless the document is wrapped by the template of its class.
+pub struct Heading;
+
+impl CommandHandler for Heading {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let name = cmd.name_tok().unwrap();
+        let name = &name.text()[1..];
+        let Some(depth) = depth(name).filter(|_| matches!(cx.mode(), LaTeXMode::Text)) else {
+            return cx.convert_command(f, cmd);
+        };
+        let level = heading_level(cx, cmd, depth);
+
+        let mut short = None;
+        let mut title = None;
+        for arg in cmd.arguments() {
+            match arg.first_child().map(|node| node.kind()) {
+                Some(SyntaxKind::ItemBracket) => short = Some(arg),
+                _ => title = Some(arg),
+            }
+        }
+
+        // number the headings like LaTeX, unless the template of the class does
+        if !cx.conv.heading_numbering && cx.conv.template.is_none() {
+            f.write_str("#set heading(numbering: \"1.1\");")?;
+        }
+        cx.conv.heading_numbering = true;
+        write!(f, "#heading(level: {level}")?;
+        if name.ends_with('*') {
+            f.write_str(", numbering: none, outlined: false")?;
+        }
+        f.write_str(")[")?;
+        match short {
+            Some(short) => {
+                cx.conv.helpers.insert("mitex-short-title");
+                f.write_str("#mitex-short-title([")?;
+                cx.convert_arg(f, &short)?;
+                f.write_str("], [")?;
+                if let Some(title) = title {
+                    cx.convert_arg(f, &title)?;
+                }
+                f.write_str("])")?;
+            }
+            None => {
+                if let Some(title) = title {
+                    cx.convert_arg(f, &title)?;
+                }
+            }
+        }
+        f.write_str("];")?;
+
+        // attach the following label to the heading
+        let label = cmd
+            .syntax()
+            .siblings_with_tokens(rowan::Direction::Next)
+            .skip(1)
+            .find(|elem| {
+                !matches!(
+                    elem.kind(),
+                    SyntaxKind::TokenWhiteSpace | SyntaxKind::TokenLineBreak
+                )
+            })
+            .and_then(|elem| CmdItem::cast(elem.into_node()?))
+            .filter(|cmd| cmd.name_tok().is_some_and(|name| name.text() == "\\label"));
+        // a label without name is left to its handler, which reports it
+        let name = label.as_ref().and_then(label_name);
+        if let (Some(label), Some(name)) = (label, name) {
+            if cx.conv.emit_labels {
+                write!(f, "<{name}>")?;
+            }
+            cx.conv.attached_label = Some(label.syntax().text_range());
+        }
+        Ok(())
+    }
+}
+
+/// Converts command `\appendix`, which restarts heading numbering with letters
+pub struct Appendix;
+
+impl CommandHandler for Appendix {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        f.write_str("#counter(heading).update(0);\n#set heading(numbering: \"A.1\");")?;
+        cx.conv.heading_numbering = true;
+        Ok(())
+    }
+}
+
+/// Converts commands like `\tableofcontents` to an outline with the given
+/// arguments
+pub struct Outline(pub &'static str);
+
+impl CommandHandler for Outline {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        write!(f, "#outline({});", self.0)?;
+        Ok(())
+    }
+}
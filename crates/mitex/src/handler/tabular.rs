@@ -0,0 +1,597 @@
+use std::fmt::Write;
+use std::ops::Range;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::colspec::{parse_colspec, ColSpec, Column, MAX_REPEAT};
+use super::length::typst_length;
+use super::{arg_text, env_alias, report_unsupported, ConvertContext, EnvHandler};
+use crate::converter::{error_at, ConvertError, LaTeXEnv};
+use crate::diagnostic::{Diagnostic, DiagnosticCode};
+
+/// Converts environments `tabular`, `tabular*`, `tabularx` and `longtable`
+/// to `#table`, taking rules like `\hline` as horizontal lines
+///
+/// The column specification may contain `p{..}`, `m{..}` and `b{..}`
+/// columns, whose widths are carried over to `columns`, `X` columns taking
+/// the remaining width, as well as `>{..}`, `@{..}` and `*{n}{..}`. Cells of
+/// `\multicolumn` and `\multirow` become `table.cell`s spanning columns and
+/// rows, and `\cline` and `\cmidrule` become partial horizontal lines.
+///
+/// The tables with a width are put in a `#block` of the width. The rows of
+/// `longtable` before `\endhead` or `\endfirsthead` become the repeated
+/// `table.header`, the rows before `\endfoot` or `\endlastfoot` become the
+/// `table.footer`, and a row of `\caption` makes it a figure.
+pub struct Tabular;
+
+impl EnvHandler for Tabular {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        // skip the position like `[c]` of longtable
+        let args = env
+            .arguments()
+            .filter(|arg| {
+                !matches!(
+                    arg.first_child().map(|node| node.kind()),
+                    Some(SyntaxKind::ItemBracket)
+                )
+            })
+            .collect::<Vec<_>>();
+        // tabular* and tabularx have a width before the column specification
+        let name = env
+            .name_tok()
+            .map(|tok| tok.text().trim().to_owned())
+            .unwrap_or_default();
+        let has_width = matches!(name.as_str(), "tabular*" | "tabularx");
+        let (width, arg) = match (has_width, args.as_slice()) {
+            (true, [width, arg]) => (Some(width), arg),
+            (false, [arg]) => (None, arg),
+            _ => {
+                let message = if has_width {
+                    format!("missing width or column specification of {name}")
+                } else {
+                    format!("missing column specification of {name}")
+                };
+                return Err(error_at(
+                    DiagnosticCode::MissingArgument,
+                    message,
+                    &env.syntax().clone().into(),
+                ));
+            }
+        };
+        let width = match width {
+            Some(width) => {
+                let text = arg_text(width);
+                let typst_width = typst_length(&text);
+                if typst_width.is_none() {
+                    report_unsupported(cx, width, format!("unsupported table width: {text}"));
+                }
+                typst_width
+            }
+            None => None,
+        };
+        let spec = parse_colspec(cx, arg)?;
+        let prev = cx.conv.enter_env(LaTeXEnv::Table);
+        let res = convert_table(cx, f, env, &spec, width);
+        cx.conv.exit_env(prev);
+        res
+    }
+}
+
+/// A converted cell of a tabular
+struct Cell {
+    content: String,
+    colspan: usize,
+    /// The number of rows spanned, negative if the content is at the bottom
+    /// like `\multirow{-2}{*}{..}`
+    rowspan: isize,
+    align: Option<String>,
+}
+
+impl Cell {
+    fn is_empty(&self) -> bool {
+        self.content.trim().is_empty()
+    }
+}
+
+/// A line of a tabular, either a horizontal rule or a row of cells
+enum Line {
+    Rule(String),
+    Row(Vec<Cell>),
+}
+
+/// A part of `longtable` ended by a command like `\endhead`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Part {
+    FirstHead,
+    Head,
+    Foot,
+    LastFoot,
+}
+
+impl Part {
+    fn from_cmd(cmd: &CmdItem) -> Option<Self> {
+        Some(match &cmd.name_tok()?.text()[1..] {
+            "endfirsthead" => Self::FirstHead,
+            "endhead" => Self::Head,
+            "endfoot" => Self::Foot,
+            "endlastfoot" => Self::LastFoot,
+            _ => return None,
+        })
+    }
+}
+
+fn convert_table(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    env: &EnvItem,
+    spec: &ColSpec,
+    width: Option<String>,
+) -> Result<(), ConvertError> {
+    let typst_name = env_alias(cx.spec, env);
+
+    let mut lines = vec![];
+    // the lines of the parts ended by commands like `\endhead`
+    let mut parts: Vec<(Part, Range<usize>, Vec<Line>)> = vec![];
+    let mut caption = None;
+    let mut row: Vec<Cell> = vec![];
+    let mut cell: Vec<SyntaxElement> = vec![];
+    // whether the current row has any cell
+    let mut started = false;
+    for child in env.syntax().children_with_tokens() {
+        match child.kind() {
+            // skip \begin and \end commands
+            SyntaxKind::ItemBegin | SyntaxKind::ItemEnd => {}
+            SyntaxKind::TokenAmpersand => {
+                let column = row.iter().map(|cell| cell.colspan).sum::<usize>();
+                row.push(convert_cell(cx, &cell, spec.columns.get(column))?);
+                cell.clear();
+                started = true;
+            }
+            SyntaxKind::ItemNewLine if !started && is_caption_row(&cell) => {
+                caption = Some(convert_caption_row(cx, &cell)?);
+                cell.clear();
+            }
+            SyntaxKind::ItemNewLine => {
+                let column = row.iter().map(|cell| cell.colspan).sum::<usize>();
+                row.push(convert_cell(cx, &cell, spec.columns.get(column))?);
+                cell.clear();
+                lines.push(Line::Row(std::mem::take(&mut row)));
+                started = false;
+            }
+            // skip the leading spaces of a cell
+            SyntaxKind::TokenWhiteSpace | SyntaxKind::TokenLineBreak if cell.is_empty() => {}
+            _ => {
+                let cmd = (!started && cell.is_empty())
+                    .then(|| child.as_node().cloned().and_then(CmdItem::cast))
+                    .flatten();
+                if let Some(part) = cmd.as_ref().and_then(Part::from_cmd) {
+                    let range = child.text_range();
+                    let range = range.start().into()..range.end().into();
+                    parts.push((part, range, std::mem::take(&mut lines)));
+                    continue;
+                }
+                match cmd.and_then(|cmd| rule(cx, &cmd)) {
+                    Some(rule) => lines.push(Line::Rule(rule)),
+                    None => cell.push(child),
+                }
+            }
+        }
+    }
+    if started || !cell.is_empty() {
+        let column = row.iter().map(|cell| cell.colspan).sum::<usize>();
+        let mut cell = convert_cell(cx, &cell, spec.columns.get(column))?;
+        // the last cell ends at `\end` with the trailing spaces
+        cell.content.truncate(cell.content.trim_end().len());
+        row.push(cell);
+        lines.push(Line::Row(row));
+    }
+
+    let mut take_part = |part| {
+        let index = parts.iter().position(|(it, _, _)| *it == part)?;
+        let (_, range, lines) = parts.remove(index);
+        Some((range, lines))
+    };
+    let first_head = take_part(Part::FirstHead);
+    let head = take_part(Part::Head);
+    let last_foot = take_part(Part::LastFoot);
+    let foot = take_part(Part::Foot);
+    let header = select_part(cx, first_head, head, ["\\endfirsthead", "\\endhead"]);
+    let footer = select_part(cx, last_foot, foot, ["\\endlastfoot", "\\endfoot"]);
+
+    // convert to #table
+    let mut table = String::new();
+    table.push('#');
+    table.push_str(&typst_name);
+    table.push('(');
+    // stroke: none,
+    table.push_str("stroke: none,\n");
+    // columns: 2, or columns: (auto, 3cm, ),
+    if spec.has_widths() {
+        table.push_str("columns: (");
+        for column in &spec.columns {
+            write!(table, "{}, ", column.width.as_deref().unwrap_or("auto"))?;
+        }
+        table.push_str("),\n");
+    } else {
+        writeln!(table, "columns: {},", spec.columns.len())?;
+    }
+    // column-gutter: 1fr, for @{\extracolsep{\fill}} in tables with a width
+    if spec.fill && width.is_some() {
+        table.push_str("column-gutter: 1fr,\n");
+    }
+    // align: (left, center, right, ),
+    table.push_str("align: (");
+    for column in &spec.columns {
+        write!(table, "{}, ", column.align)?;
+    }
+    table.push_str("),\n");
+    // table.vline(x: 1),
+    for &(x, count) in &spec.vlines {
+        let stroke = if count > 1 { "1pt" } else { ".5pt" };
+        write!(table, "table.vline(stroke: {stroke}, x: {x}), ")?;
+    }
+    table.push('\n');
+    if let Some((lines, repeat)) = header {
+        writeln!(table, "table.header(repeat: {repeat},")?;
+        write_lines(&mut table, lines)?;
+        table.push_str("),\n");
+    }
+    write_lines(&mut table, lines)?;
+    if let Some((lines, repeat)) = footer {
+        writeln!(table, "table.footer(repeat: {repeat},")?;
+        write_lines(&mut table, lines)?;
+        table.push_str("),\n");
+    }
+    table.push_str(");");
+
+    if let Some(width) = width {
+        table = format!("#block(width: {width})[{table}];");
+    }
+    if let Some(caption) = caption {
+        table = format!("#figure(kind: table, caption: [{caption}])[{table}];");
+    }
+    f.write_str(&table)?;
+    Ok(())
+}
+
+/// Selects the lines of the header or footer, and whether they are repeated
+///
+/// Typst repeats the same header and footer on every page, so the first
+/// header and the last footer are taken if there are both.
+fn select_part(
+    cx: &mut ConvertContext<'_>,
+    single: Option<(Range<usize>, Vec<Line>)>,
+    repeated: Option<(Range<usize>, Vec<Line>)>,
+    [single_name, repeated_name]: [&str; 2],
+) -> Option<(Vec<Line>, bool)> {
+    match (single, repeated) {
+        (Some((_, single)), Some((range, repeated))) => {
+            if repeated.iter().any(|line| matches!(line, Line::Row(_))) {
+                cx.report(Diagnostic::warning(
+                    DiagnosticCode::Unsupported,
+                    format!(
+                        "ignored rows of {repeated_name}, repeating the rows of {single_name} instead"
+                    ),
+                    range,
+                ));
+            }
+            Some((single, true))
+        }
+        (Some((_, single)), None) => Some((single, false)),
+        (None, Some((_, repeated))) => Some((repeated, true)),
+        (None, None) => None,
+    }
+}
+
+/// Writes the rows and rules of a part of a table
+fn write_lines(f: &mut String, mut lines: Vec<Line>) -> Result<(), ConvertError> {
+    move_bottom_multirows(&mut lines);
+    // the number of rows still spanned by a cell above, for each column
+    let mut spanned: Vec<usize> = vec![];
+    for line in lines {
+        let row = match line {
+            Line::Rule(rule) => {
+                f.write_str(&rule)?;
+                continue;
+            }
+            Line::Row(row) => row,
+        };
+        let mut cells = vec![];
+        let mut column = 0;
+        for cell in row {
+            let end = column + cell.colspan;
+            if spanned.len() < end {
+                spanned.resize(end, 0);
+            }
+            // the placeholder of a cell spanning rows
+            if spanned[column] > 0 && cell.is_empty() {
+                column = end;
+                continue;
+            }
+            let rowspan = cell.rowspan.unsigned_abs().max(1);
+            for spanned in &mut spanned[column..end] {
+                *spanned = rowspan;
+            }
+            column = end;
+
+            let mut args = vec![];
+            if cell.colspan > 1 {
+                args.push(format!("colspan: {}", cell.colspan));
+            }
+            if rowspan > 1 {
+                args.push(format!("rowspan: {rowspan}"));
+            }
+            if let Some(align) = cell.align {
+                args.push(format!("align: {align}"));
+            }
+            let content = if cell.content.trim().is_empty() {
+                ""
+            } else {
+                &cell.content
+            };
+            if args.is_empty() {
+                cells.push(format!("[{content}]"));
+            } else {
+                cells.push(format!("table.cell({})[{content}]", args.join(", ")));
+            }
+        }
+        for spanned in &mut spanned {
+            *spanned = spanned.saturating_sub(1);
+        }
+        if !cells.is_empty() {
+            f.write_str(&cells.join(", "))?;
+            f.write_str(",\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether a row consists of `\caption` and `\label` only, as the caption
+/// of `longtable`
+fn is_caption_row(elems: &[SyntaxElement]) -> bool {
+    let mut has_caption = false;
+    for elem in elems {
+        if matches!(
+            elem.kind(),
+            SyntaxKind::TokenWhiteSpace | SyntaxKind::TokenLineBreak
+        ) {
+            continue;
+        }
+        let name = elem
+            .as_node()
+            .cloned()
+            .and_then(CmdItem::cast)
+            .and_then(|cmd| cmd.name_tok())
+            .map(|name| name.text().to_owned());
+        match name.as_deref() {
+            Some("\\caption") => has_caption = true,
+            Some("\\label") => {}
+            _ => return false,
+        }
+    }
+    has_caption
+}
+
+/// Converts a row of `\caption` to the caption, attaching the label to the
+/// table
+fn convert_caption_row(
+    cx: &mut ConvertContext<'_>,
+    elems: &[SyntaxElement],
+) -> Result<String, ConvertError> {
+    let mut caption = String::new();
+    for elem in elems {
+        let Some(cmd) = elem.as_node().cloned().and_then(CmdItem::cast) else {
+            continue;
+        };
+        if cmd
+            .name_tok()
+            .is_some_and(|name| name.text() == "\\caption")
+        {
+            if let Some(arg) = cmd.arguments().next() {
+                cx.convert_arg(&mut caption, &arg)?;
+            }
+        } else {
+            cx.convert(&mut String::new(), elem.clone())?;
+        }
+    }
+    Ok(caption)
+}
+
+/// Converts the elements of a cell in the given column
+fn convert_cell(
+    cx: &mut ConvertContext<'_>,
+    elems: &[SyntaxElement],
+    column: Option<&Column>,
+) -> Result<Cell, ConvertError> {
+    let significant = elems
+        .iter()
+        .filter(|elem| {
+            !matches!(
+                elem.kind(),
+                SyntaxKind::TokenWhiteSpace | SyntaxKind::TokenLineBreak
+            )
+        })
+        .collect::<Vec<_>>();
+    let span = match significant.as_slice() {
+        [elem] => elem.as_node().cloned().and_then(CmdItem::cast),
+        _ => None,
+    };
+    let span_name = span
+        .as_ref()
+        .and_then(|cmd| cmd.name_tok())
+        .map(|name| name.text().to_owned());
+    match (span, span_name.as_deref()) {
+        (Some(cmd), Some("\\multicolumn")) => {
+            let args = cmd.arguments().collect::<Vec<_>>();
+            let [count, spec, content] = args.as_slice() else {
+                return plain_cell(cx, elems, column);
+            };
+            let colspan = arg_text(count).parse::<usize>().unwrap_or(1).max(1);
+            let colspan = capped_span(cx, count, colspan);
+            let spec = parse_colspec(cx, spec)?;
+            let column = spec.columns.first();
+            let mut cell = convert_cell(cx, &arg_elements(content), column)?;
+            cell.colspan = colspan;
+            cell.align = column.map(|column| column.align.clone());
+            Ok(cell)
+        }
+        (Some(cmd), Some("\\multirow")) => {
+            // \multirow[vpos]{rows}[bigstruts]{width}[vmove]{text}
+            let args = cmd
+                .arguments()
+                .filter(|arg| {
+                    !matches!(
+                        arg.first_child().map(|node| node.kind()),
+                        Some(SyntaxKind::ItemBracket)
+                    )
+                })
+                .collect::<Vec<_>>();
+            let [count, _width, content] = args.as_slice() else {
+                return plain_cell(cx, elems, column);
+            };
+            let rowspan = arg_text(count).parse::<isize>().unwrap_or(1);
+            let capped = capped_span(cx, count, rowspan.unsigned_abs()) as isize;
+            let rowspan = if rowspan < 0 { -capped } else { capped };
+            let mut cell = convert_cell(cx, &arg_elements(content), column)?;
+            cell.rowspan = if rowspan == 0 { 1 } else { rowspan };
+            Ok(cell)
+        }
+        _ => plain_cell(cx, elems, column),
+    }
+}
+
+/// Clamps the count of spanned columns or rows to [`MAX_REPEAT`], reporting
+/// a warning if it is above
+fn capped_span(cx: &mut ConvertContext<'_>, count: &SyntaxNode, span: usize) -> usize {
+    if span <= MAX_REPEAT {
+        return span;
+    }
+    let message = format!("span count above {MAX_REPEAT}: {span}");
+    report_unsupported(cx, count, message);
+    MAX_REPEAT
+}
+
+/// Converts the elements of a cell without spans, applying the declarations
+/// of the column
+fn plain_cell(
+    cx: &mut ConvertContext<'_>,
+    elems: &[SyntaxElement],
+    column: Option<&Column>,
+) -> Result<Cell, ConvertError> {
+    let mut content = String::new();
+    for elem in elems {
+        cx.convert(&mut content, elem.clone())?;
+    }
+    if !content.trim().is_empty() {
+        if let Some(column) = column {
+            if column.emph {
+                content = format!("#emph[{content}]");
+            }
+            if column.strong {
+                content = format!("#strong[{content}]");
+            }
+        }
+    }
+    Ok(Cell {
+        content,
+        colspan: 1,
+        rowspan: 1,
+        align: None,
+    })
+}
+
+/// Moves the content of `\multirow{-n}` cells to the top row they span
+fn move_bottom_multirows(lines: &mut [Line]) {
+    let mut rows = lines
+        .iter_mut()
+        .filter_map(|line| match line {
+            Line::Row(row) => Some(row),
+            Line::Rule(_) => None,
+        })
+        .collect::<Vec<_>>();
+    for index in 0..rows.len() {
+        for cell_index in 0..rows[index].len() {
+            let rowspan = rows[index][cell_index].rowspan;
+            if rowspan >= 0 {
+                continue;
+            }
+            let span = rowspan.unsigned_abs();
+            let column = rows[index][..cell_index]
+                .iter()
+                .map(|cell| cell.colspan)
+                .sum::<usize>();
+            let top = (index + 1).checked_sub(span);
+            let target = top.and_then(|top| {
+                let mut start = 0;
+                rows[top].iter().position(|cell| {
+                    let found = start == column;
+                    start += cell.colspan;
+                    found
+                })
+            });
+            match (top, target) {
+                (Some(top), Some(target)) if rows[top][target].is_empty() => {
+                    let cell = &mut rows[index][cell_index];
+                    let moved = Cell {
+                        content: std::mem::take(&mut cell.content),
+                        colspan: cell.colspan,
+                        rowspan: span as isize,
+                        align: cell.align.take(),
+                    };
+                    cell.rowspan = 1;
+                    rows[top][target] = moved;
+                }
+                // no room above, so span down instead
+                _ => rows[index][cell_index].rowspan = span as isize,
+            }
+        }
+    }
+}
+
+/// Converts a rule command like `\hline` or `\cline{2-3}` to a horizontal
+/// line, or to nothing with a warning if the range of columns is invalid
+fn rule(cx: &mut ConvertContext<'_>, cmd: &CmdItem) -> Option<String> {
+    let name = cmd.name_tok()?;
+    Some(match &name.text()[1..] {
+        "hline" | "midrule" => "table.hline(stroke: .5pt),\n".to_owned(),
+        "toprule" | "bottomrule" => "table.hline(stroke: 1pt),\n".to_owned(),
+        "cline" | "cmidrule" => {
+            let range = arg_text(&cmd.arguments().last()?);
+            let (start, end) = range.split_once('-').unwrap_or((&range, &range));
+            let start = start.trim().parse::<usize>().ok()?;
+            let end = end.trim().parse::<usize>().ok()?;
+            if start < 1 || start > end {
+                let message = format!("ignored rule of invalid columns: {range}");
+                report_unsupported(cx, cmd.syntax(), message);
+                return Some(String::new());
+            }
+            format!(
+                "table.hline(start: {}, end: {end}, stroke: .5pt),\n",
+                start - 1
+            )
+        }
+        _ => return None,
+    })
+}
+
+/// The elements of an argument without the surrounding braces
+fn arg_elements(arg: &SyntaxNode) -> Vec<SyntaxElement> {
+    match arg.first_child() {
+        Some(curly) if curly.kind() == SyntaxKind::ItemCurly => curly
+            .children_with_tokens()
+            .filter(|elem| {
+                !matches!(
+                    elem.kind(),
+                    SyntaxKind::TokenLBrace | SyntaxKind::TokenRBrace
+                )
+            })
+            .collect(),
+        _ => arg.children_with_tokens().collect(),
+    }
+}
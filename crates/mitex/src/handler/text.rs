@@ -0,0 +1,23 @@
+use mitex_parser::syntax::CmdItem;
+
+use super::{CommandHandler, ConvertContext};
+use crate::converter::{ConvertError, LaTeXMode};
+
+/// Converts a symbol like `\ldots` or `\,` to the given text in text mode,
+/// and according to the spec in math mode
+pub struct TextSymbol(pub &'static str);
+
+impl CommandHandler for TextSymbol {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        f.push_str(self.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxKind};
+use rowan::ast::AstNode;
+
+use super::section::{depth, heading_level};
+use super::{arg_text, report_unsupported, CommandHandler, ConvertContext, EnvHandler};
+use crate::converter::{write_str_literal, ConvertError, LaTeXEnv};
+
+/// The styles of amsthm, where `plain` is the default
+const STYLES: &[&str] = &["plain", "definition", "remark"];
+
+/// A theorem-like environment declared by `\newtheorem`
+#[derive(Debug, Clone)]
+pub(crate) struct Theorem {
+    /// The kind of the figure, i.e. the name of the environment whose counter
+    /// is used
+    kind: String,
+    /// The name in the head like `Theorem`, in Typst markup
+    supplement: String,
+    /// Whether the theorem is numbered, i.e. not declared by `\newtheorem*`
+    numbered: bool,
+    /// The heading level the number is within, e.g. 1 for `[section]`
+    within: Option<i8>,
+    /// The style set by `\theoremstyle` when it is declared
+    style: String,
+}
+
+/// Records the environment declared by `\newtheorem{name}{Supplement}[within]`,
+/// `\newtheorem{name}[shared]{Supplement}` or `\newtheorem*{name}{Supplement}`
+/// for the following environments, without output
+pub struct NewTheorem;
+
+impl CommandHandler for NewTheorem {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let mut args = vec![];
+        let mut shared = None;
+        let mut within = None;
+        for arg in cmd.arguments() {
+            match arg.first_child().map(|child| child.kind()) {
+                // the counter to share comes between the name and the supplement
+                Some(SyntaxKind::ItemBracket) if args.len() == 1 => shared = Some(arg),
+                Some(SyntaxKind::ItemBracket) => within = Some(arg),
+                _ => args.push(arg),
+            }
+        }
+        let [name, supplement] = &args[..] else {
+            return Ok(());
+        };
+        let name = arg_text(name);
+        let mut theorem = Theorem {
+            kind: name.clone(),
+            supplement: String::new(),
+            numbered: !cmd.name_tok().unwrap().text().ends_with('*'),
+            within: None,
+            style: cx.conv.theorem_style.clone(),
+        };
+        cx.convert_arg(&mut theorem.supplement, supplement)?;
+        theorem.supplement = theorem.supplement.trim().to_owned();
+
+        if let Some(shared) = shared {
+            match cx.conv.theorems.get(&arg_text(&shared)) {
+                Some(other) => {
+                    theorem.kind = other.kind.clone();
+                    theorem.within = other.within;
+                }
+                None => report_unsupported(
+                    cx,
+                    &shared,
+                    format!("unknown theorem counter: {}", arg_text(&shared)),
+                ),
+            }
+        }
+        if let Some(within) = within {
+            match depth(&arg_text(&within)) {
+                Some(depth) => theorem.within = Some(heading_level(cx, cmd, depth)),
+                None => report_unsupported(
+                    cx,
+                    &within,
+                    format!("unsupported theorem counter: {}", arg_text(&within)),
+                ),
+            }
+        }
+        cx.conv.theorems.insert(name, theorem);
+        Ok(())
+    }
+}
+
+/// Sets the style of the theorems declared after `\theoremstyle{style}`
+pub struct TheoremStyle;
+
+impl CommandHandler for TheoremStyle {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().next() else {
+            return Ok(());
+        };
+        let style = arg_text(&arg);
+        if STYLES.contains(&style.as_str()) {
+            cx.conv.theorem_style = style;
+        } else {
+            report_unsupported(cx, &arg, format!("unsupported theorem style: {style}"));
+        }
+        Ok(())
+    }
+}
+
+/// Converts the environments declared by `\newtheorem` like
+/// `\begin{thm}[title]` to `#mitex-theorem`, a figure of the kind of the
+/// shared counter
+///
+/// A `\label` in the body is attached to the figure, so that it can be
+/// referenced like `@thm`.
+pub struct TheoremEnv;
+
+impl EnvHandler for TheoremEnv {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let name = env.name_tok().unwrap();
+        let Some(theorem) = cx.conv.theorems.get(name.text().trim()).cloned() else {
+            return cx.convert_env_body(f, env);
+        };
+        // the title is not parsed as an argument since the environment is not
+        // in the spec
+        let children = env
+            .syntax()
+            .children_with_tokens()
+            .filter(|child| !matches!(child.kind(), SyntaxKind::ItemBegin | SyntaxKind::ItemEnd))
+            .collect::<Vec<_>>();
+        let (title, body) = split_title(&children);
+
+        let outer_label = cx.conv.label.take();
+        let prev = cx.conv.enter_env(LaTeXEnv::Theorem);
+        let res: Result<_, ConvertError> = (|| {
+            let title = match title {
+                Some(title) => Some(convert_elements(cx, title)?),
+                None => None,
+            };
+            Ok((title, convert_elements(cx, body)?))
+        })();
+        cx.conv.exit_env(prev);
+        let label = std::mem::replace(&mut cx.conv.label, outer_label);
+        let (title, body) = res?;
+
+        cx.conv.helpers.insert("mitex-theorem");
+        f.write_str("#mitex-theorem(kind: ")?;
+        write_str_literal(f, &theorem.kind)?;
+        write!(f, ", supplement: [{}]", theorem.supplement)?;
+        if !theorem.numbered {
+            f.write_str(", numbered: false")?;
+        }
+        if let Some(within) = theorem.within {
+            write!(f, ", within: {within}")?;
+        }
+        if theorem.style != "plain" {
+            f.write_str(", style: ")?;
+            write_str_literal(f, &theorem.style)?;
+        }
+        if let Some(title) = title {
+            write!(f, ", title: [{title}]")?;
+        }
+        if let Some(label) = label.filter(|_| cx.conv.emit_labels) {
+            write!(f, ", label: <{label}>")?;
+        }
+        write!(f, ")[{body}];")?;
+        Ok(())
+    }
+}
+
+/// Converts environment `proof[title]` of amsthm to `#mitex-proof`, which ends
+/// with a QED marker
+pub struct Proof;
+
+impl EnvHandler for Proof {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        cx.conv.helpers.insert("mitex-proof");
+        f.write_str("#mitex-proof")?;
+        if let Some(title) = env.arguments().next() {
+            f.write_str("(title: [")?;
+            cx.convert_arg(f, &title)?;
+            f.write_str("])")?;
+        }
+        let mut body = String::new();
+        cx.convert_env_body(&mut body, env)?;
+        write!(f, "[{}];", body.trim())?;
+        Ok(())
+    }
+}
+
+/// Splits the optional title in brackets like `[title]` at the start of the
+/// body of an environment from the rest of the body
+fn split_title(children: &[SyntaxElement]) -> (Option<&[SyntaxElement]>, &[SyntaxElement]) {
+    let start = children
+        .iter()
+        .position(|child| {
+            !matches!(
+                child.kind(),
+                SyntaxKind::TokenWhiteSpace | SyntaxKind::TokenLineBreak
+            )
+        })
+        .unwrap_or(children.len());
+    if children.get(start).map(|child| child.kind()) != Some(SyntaxKind::TokenLBracket) {
+        return (None, children);
+    }
+    let mut depth = 0usize;
+    for (index, child) in children.iter().enumerate().skip(start) {
+        match child.kind() {
+            SyntaxKind::TokenLBracket => depth += 1,
+            SyntaxKind::TokenRBracket => {
+                depth -= 1;
+                if depth == 0 {
+                    return (Some(&children[start + 1..index]), &children[index + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    // not closed
+    (None, children)
+}
+
+/// Converts a sequence of elements, trimmed
+fn convert_elements(
+    cx: &mut ConvertContext<'_>,
+    elements: &[SyntaxElement],
+) -> Result<String, ConvertError> {
+    let mut converted = String::new();
+    for elem in elements {
+        cx.convert(&mut converted, elem.clone())?;
+    }
+    Ok(converted.trim().to_owned())
+}
@@ -0,0 +1,418 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::citation::{collapse_spaces, push_plain_text};
+use super::{arg_text, report_unsupported, CommandHandler, ConvertContext, EnvHandler};
+use crate::converter::{ConvertError, LaTeXMode};
+use crate::document::Author;
+
+/// Records the class and the options of `\documentclass[options]{class}`,
+/// without output
+pub struct DocumentClass;
+
+impl CommandHandler for DocumentClass {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let metadata = &mut cx.conv.metadata;
+        for arg in cmd.arguments() {
+            let text = arg_text(&arg);
+            match arg.first_child().map(|child| child.kind()) {
+                Some(SyntaxKind::ItemBracket) => {
+                    let options = text.split(',').map(str::trim).filter(|o| !o.is_empty());
+                    metadata.class_options = options.map(str::to_owned).collect();
+                }
+                _ => metadata.class = Some(text),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the title of `\title[short]{title}` or the date of `\date{date}`
+/// for `\maketitle`, without output
+pub struct TitleField;
+
+impl CommandHandler for TitleField {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        let text = convert_text(cx, &content_of(&arg))?;
+        let metadata = &mut cx.conv.metadata;
+        match cmd.name_tok().unwrap().text() {
+            "\\date" => metadata.date = Some(text),
+            _ => metadata.title = Some(text),
+        }
+        Ok(())
+    }
+}
+
+/// Records the authors of `\author{name \\ affiliation \and name}`, without
+/// output
+///
+/// The first line of an author is the name, and the following lines are the
+/// affiliations. `\thanks` in an author becomes a footnote of the author. The
+/// blocks of IEEEtran like `\IEEEauthorblockN{name}\IEEEauthorblockA{..}`
+/// give the name and the affiliations likewise.
+pub struct AuthorField;
+
+impl CommandHandler for AuthorField {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        for elems in split_by(&content_of(&arg), |elem| is_command(elem, "\\and")) {
+            let authors = if elems
+                .iter()
+                .any(|elem| is_command(elem, "\\IEEEauthorblockN"))
+            {
+                author_blocks(&elems)
+            } else {
+                vec![split_by(&elems, is_line_break)]
+            };
+            for lines in authors {
+                push_author(cx, lines)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records an author of the lines, the name followed by the affiliations
+fn push_author(
+    cx: &mut ConvertContext<'_>,
+    lines: Vec<Vec<SyntaxElement>>,
+) -> Result<(), ConvertError> {
+    let mut thanks = vec![];
+    let mut texts = vec![];
+    for line in lines {
+        let mut text = vec![];
+        for elem in line {
+            match cmd_of(&elem).filter(|_| is_command(&elem, "\\thanks")) {
+                Some(cmd) => {
+                    if let Some(arg) = cmd.arguments().last() {
+                        thanks.push(convert_text(cx, &content_of(&arg))?);
+                    }
+                }
+                None => text.push(elem),
+            }
+        }
+        texts.push(text);
+    }
+    let mut lines = texts.into_iter();
+    let name = lines.next().unwrap_or_default();
+    let mut plain_name = String::new();
+    for elem in &name {
+        push_plain_text(&mut plain_name, elem);
+    }
+    let mut affiliations = vec![];
+    for line in lines {
+        let text = convert_text(cx, &line)?;
+        if !text.is_empty() {
+            affiliations.push(text);
+        }
+    }
+    let author = Author {
+        name: convert_text(cx, &name)?,
+        plain_name: collapse_spaces(&plain_name),
+        affiliations,
+        thanks,
+    };
+    if !author.name.is_empty() {
+        cx.conv.metadata.authors.push(author);
+    }
+    Ok(())
+}
+
+/// The lines of the authors given by `\IEEEauthorblockN{name}` and the
+/// following `\IEEEauthorblockA{affiliation}`
+fn author_blocks(elems: &[SyntaxElement]) -> Vec<Vec<Vec<SyntaxElement>>> {
+    let mut authors: Vec<Vec<Vec<SyntaxElement>>> = vec![];
+    for elem in elems {
+        let Some(cmd) = cmd_of(elem) else {
+            continue;
+        };
+        let Some(arg) = cmd.arguments().last() else {
+            continue;
+        };
+        let lines = split_by(&content_of(&arg), is_line_break);
+        match cmd.name_tok().map(|tok| tok.text().to_owned()).as_deref() {
+            Some("\\IEEEauthorblockN") => authors.push(lines),
+            Some("\\IEEEauthorblockA") => {
+                if let Some(author) = authors.last_mut() {
+                    author.extend(lines);
+                }
+            }
+            _ => {}
+        }
+    }
+    authors
+}
+
+/// Records the affiliation of `\affiliation{affiliation}` for the last
+/// author, without output
+pub struct Affiliation;
+
+impl CommandHandler for Affiliation {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let Some(arg) = cmd.arguments().last() else {
+            return Ok(());
+        };
+        let text = convert_text(cx, &content_of(&arg))?;
+        match cx.conv.metadata.authors.last_mut() {
+            Some(author) => author.affiliations.push(text),
+            None => {
+                report_unsupported(cx, cmd.syntax(), "affiliation without author");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the keywords of `\keywords{a, b; c}`, without output
+pub struct Keywords;
+
+impl CommandHandler for Keywords {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if let Some(arg) = cmd.arguments().last() {
+            push_keywords(cx, content_of(&arg));
+        }
+        Ok(())
+    }
+}
+
+/// Records the keywords of environment `IEEEkeywords` like `\keywords`,
+/// without output
+pub struct KeywordsEnv;
+
+impl EnvHandler for KeywordsEnv {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        _f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let body = env
+            .syntax()
+            .children_with_tokens()
+            .filter(|child| !matches!(child.kind(), SyntaxKind::ItemBegin | SyntaxKind::ItemEnd));
+        push_keywords(cx, body);
+        Ok(())
+    }
+}
+
+/// Records the keywords separated by `,` or `;` in the elements
+fn push_keywords(cx: &mut ConvertContext<'_>, elems: impl IntoIterator<Item = SyntaxElement>) {
+    let mut text = String::new();
+    for elem in elems {
+        push_plain_text(&mut text, &elem);
+    }
+    let keywords = text.split([',', ';']).map(collapse_spaces);
+    let keywords = keywords.filter(|keyword| !keyword.is_empty());
+    cx.conv.metadata.keywords.extend(keywords);
+}
+
+/// Converts command `\maketitle` to `#mitex-title(..)`, the title block with
+/// the title, the authors and the date recorded before it
+///
+/// Nothing is written if the document is wrapped by the template of its class,
+/// which shows the title block itself.
+pub struct MakeTitle;
+
+impl CommandHandler for MakeTitle {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        if matches!(cx.mode(), LaTeXMode::Math) {
+            return cx.convert_command(f, cmd);
+        }
+        if cx.conv.template.is_some() {
+            return Ok(());
+        }
+        let metadata = &cx.conv.metadata;
+        let mut args = vec![];
+        if let Some(title) = &metadata.title {
+            args.push(format!("title: [{title}]"));
+        }
+        if !metadata.authors.is_empty() {
+            let mut authors = String::from("authors: (");
+            for author in &metadata.authors {
+                write!(authors, "(name: [{}", author.name)?;
+                for thanks in &author.thanks {
+                    write!(authors, "#footnote[{thanks}]")?;
+                }
+                authors.push(']');
+                if !author.affiliations.is_empty() {
+                    let affiliations = author.affiliations.join(" \\ ");
+                    write!(authors, ", affiliation: [{affiliations}]")?;
+                }
+                authors.push_str("), ");
+            }
+            authors.truncate(authors.len() - 1);
+            authors.push(')');
+            args.push(authors);
+        }
+        if let Some(date) = &metadata.date {
+            args.push(format!("date: [{date}]"));
+        }
+        cx.conv.helpers.insert("mitex-title");
+        write!(f, "#mitex-title({});", args.join(", "))?;
+        Ok(())
+    }
+}
+
+/// Converts command `\today` to the date of compiling the Typst document,
+/// like `January 1, 2024`
+pub struct Today;
+
+impl CommandHandler for Today {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        _cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        f.write_str(
+            r#"#datetime.today().display("[month repr:long] [day padding:none], [year]")"#,
+        )?;
+        if matches!(cx.mode(), LaTeXMode::Text) {
+            f.write_char(';')?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts environment `abstract` to `#mitex-abstract[body]`, recording the
+/// body as the abstract of the document
+///
+/// Only the body is recorded if the document is wrapped by the template of its
+/// class, which shows the abstract itself.
+pub struct Abstract;
+
+impl EnvHandler for Abstract {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let mut body = String::new();
+        cx.convert_env_body(&mut body, env)?;
+        let body = body.trim();
+        if cx.conv.template.is_none() {
+            cx.conv.helpers.insert("mitex-abstract");
+            write!(f, "#mitex-abstract[{body}];")?;
+        }
+        cx.conv.metadata.r#abstract = Some(body.to_owned());
+        Ok(())
+    }
+}
+
+/// Converts environment `document` to its body
+///
+/// In the document mode, the body is converted directly instead, dropping
+/// anything outside the environment.
+pub struct Document;
+
+impl EnvHandler for Document {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        cx.convert_env_body(f, env)
+    }
+}
+
+/// Converts elements in text mode, trimmed
+fn convert_text(
+    cx: &mut ConvertContext<'_>,
+    elems: &[SyntaxElement],
+) -> Result<String, ConvertError> {
+    let mut text = String::new();
+    let prev = cx.conv.enter_mode(LaTeXMode::Text);
+    let res = elems
+        .iter()
+        .try_for_each(|elem| cx.convert(&mut text, elem.clone()));
+    cx.conv.exit_mode(prev);
+    res?;
+    Ok(text.trim().to_owned())
+}
+
+/// The elements in an argument without the surrounding braces
+fn content_of(arg: &SyntaxNode) -> Vec<SyntaxElement> {
+    let group = arg.first_child().unwrap_or_else(|| arg.clone());
+    group
+        .children_with_tokens()
+        .filter(|elem| {
+            !matches!(
+                elem.kind(),
+                SyntaxKind::TokenLBrace
+                    | SyntaxKind::TokenRBrace
+                    | SyntaxKind::TokenLBracket
+                    | SyntaxKind::TokenRBracket
+            )
+        })
+        .collect()
+}
+
+/// Splits elements by the separators like `\and`, dropping the separators
+fn split_by(
+    elems: &[SyntaxElement],
+    is_sep: impl Fn(&SyntaxElement) -> bool,
+) -> Vec<Vec<SyntaxElement>> {
+    let mut parts = vec![vec![]];
+    for elem in elems {
+        if is_sep(elem) {
+            parts.push(vec![]);
+        } else {
+            parts.last_mut().unwrap().push(elem.clone());
+        }
+    }
+    parts
+}
+
+/// The command of an element, if it is one
+fn cmd_of(elem: &SyntaxElement) -> Option<CmdItem> {
+    elem.as_node().cloned().and_then(CmdItem::cast)
+}
+
+/// Whether an element is a line break `\\`
+fn is_line_break(elem: &SyntaxElement) -> bool {
+    elem.kind() == SyntaxKind::ItemNewLine
+}
+
+/// Whether an element is the command `name`
+fn is_command(elem: &SyntaxElement, name: &str) -> bool {
+    cmd_of(elem).is_some_and(|cmd| cmd.name_tok().is_some_and(|tok| tok.text() == name))
+}
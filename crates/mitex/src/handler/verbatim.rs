@@ -0,0 +1,136 @@
+use std::fmt::Write;
+
+use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxKind, SyntaxNode};
+use rowan::ast::AstNode;
+
+use super::{arg_text, split_options, CommandHandler, ConvertContext, EnvHandler};
+use crate::converter::{write_str_literal, ConvertError, LaTeXMode};
+
+/// Converts command `\verb|a_b|`, `\lstinline[language=C]|int x|` and
+/// `\mintinline{c}{int x}` to `#raw("a_b")`
+///
+/// `\verb*` shows the spaces as visible space characters like LaTeX does.
+pub struct Verb;
+
+impl CommandHandler for Verb {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        cmd: &CmdItem,
+    ) -> Result<(), ConvertError> {
+        let name = cmd.name_tok().unwrap();
+        let visible_space = name.text() == "\\verb*";
+        let text = verbatim_arg(cmd);
+        let text = match visible_space {
+            true => text.replace(' ', "␣"),
+            false => text,
+        };
+        let lang = match name.text() {
+            "\\mintinline" => cmd
+                .arguments()
+                .find(|arg| is_arg_of(arg, SyntaxKind::ItemCurly))
+                .map(|arg| arg_text(&arg)),
+            _ => language_option(cmd.arguments()),
+        };
+        write_raw(cx, f, false, lang.as_deref(), &text)
+    }
+}
+
+/// Converts environment `verbatim`, `lstlisting` and `minted` to
+/// `#raw(block: true, "...")`
+///
+/// The language of `lstlisting` is given by the option `language=Python`,
+/// and the language of `minted` is given by its argument.
+pub struct Verbatim;
+
+impl EnvHandler for Verbatim {
+    fn convert(
+        &self,
+        cx: &mut ConvertContext<'_>,
+        f: &mut String,
+        env: &EnvItem,
+    ) -> Result<(), ConvertError> {
+        let text = verbatim_text(env.syntax()).unwrap_or_default();
+        // the line break after `\begin` and the indent before `\end`
+        let text = text
+            .strip_prefix("\r\n")
+            .or_else(|| text.strip_prefix('\n'))
+            .unwrap_or(&text);
+        let text = text.trim_end_matches([' ', '\t']);
+        let text = text
+            .strip_suffix("\r\n")
+            .or_else(|| text.strip_suffix('\n'))
+            .unwrap_or(text);
+        let lang = match env.name_tok().unwrap().text().trim() {
+            "minted" => env
+                .arguments()
+                .find(|arg| is_arg_of(arg, SyntaxKind::ItemCurly))
+                .map(|arg| arg_text(&arg)),
+            _ => language_option(env.arguments()),
+        };
+        write_raw(cx, f, true, lang.as_deref(), text)
+    }
+}
+
+/// Writes `#raw(block: true, lang: "c", "...")`, ended with `;` in text mode
+fn write_raw(
+    cx: &mut ConvertContext<'_>,
+    f: &mut String,
+    block: bool,
+    lang: Option<&str>,
+    text: &str,
+) -> Result<(), ConvertError> {
+    f.write_str("#raw(")?;
+    if block {
+        f.write_str("block: true, ")?;
+    }
+    if let Some(lang) = lang.filter(|lang| !lang.is_empty()) {
+        f.write_str("lang: ")?;
+        write_str_literal(f, &lang.to_lowercase())?;
+        f.write_str(", ")?;
+    }
+    write_str_literal(f, text)?;
+    f.write_char(')')?;
+    if matches!(cx.mode(), LaTeXMode::Text) {
+        f.write_char(';')?;
+    }
+    Ok(())
+}
+
+/// The raw text lexed for a verbatim command or environment
+fn verbatim_text(node: &SyntaxNode) -> Option<String> {
+    node.descendants_with_tokens()
+        .find(|elem| elem.kind() == SyntaxKind::TokenVerbatim)
+        .map(|elem| elem.as_token().unwrap().text().to_owned())
+}
+
+/// The raw text of the verbatim argument of a command, without the
+/// delimiters which are a pair of braces or the same char
+pub(super) fn verbatim_arg(cmd: &CmdItem) -> String {
+    let text = verbatim_text(cmd.syntax()).unwrap_or_default();
+    let mut chars = text.chars();
+    chars.next();
+    chars.next_back();
+    chars.as_str().to_owned()
+}
+
+/// The language in options like `[language=Python, numbers=left]`
+fn language_option(mut args: impl Iterator<Item = SyntaxNode>) -> Option<String> {
+    let options = args.find(|arg| is_arg_of(arg, SyntaxKind::ItemBracket))?;
+    let options = arg_text(&options);
+    let lang = split_options(&options)
+        .into_iter()
+        .find_map(|(key, value)| value.filter(|_| key == "language"))?;
+    // the dialect like `{[ANSI]C}` is ignored
+    let lang = lang.trim_start_matches('{').trim_end_matches('}');
+    let lang = match lang.strip_prefix('[') {
+        Some(lang) => lang.split_once(']').map_or(lang, |(_, lang)| lang),
+        None => lang,
+    };
+    Some(lang.trim().to_owned())
+}
+
+fn is_arg_of(arg: &SyntaxNode, kind: SyntaxKind) -> bool {
+    arg.first_child().is_some_and(|child| child.kind() == kind)
+}
@@ -1,22 +1,130 @@
+mod bibliography;
 mod converter;
+mod diagnostic;
+mod document;
+mod handler;
+mod markup;
+mod options;
+mod prelude;
+mod source_map;
+mod typography;
 
+pub use bibliography::{hayagriva_yaml, BibItem};
+pub use converter::{ConvertError, Converted, LaTeXMode};
+pub use diagnostic::{Diagnostic, DiagnosticCode, Severity};
+pub use document::{Author, DocumentMetadata, DocumentTemplate, Layout};
+pub use handler::{CommandHandler, ConvertContext, EnvHandler, Handlers};
 pub use mitex_parser::command_preludes;
-use mitex_parser::parse;
-use mitex_parser::parse_without_macro;
 pub use mitex_parser::spec::*;
+pub use mitex_parser::syntax::{CmdItem, EnvItem, SyntaxElement, SyntaxNode};
+pub use mitex_parser::InputMap;
+pub use options::{ConvertOptions, MathStyle, UnknownCommandPolicy};
+pub use prelude::typst_prelude;
+pub use source_map::SourceMapping;
 
-use converter::convert_inner;
-use converter::LaTeXMode;
+use converter::{convert_inner, convert_node_inner};
+use mitex_spec_gen::DEFAULT_SPEC;
+
+/// Converts LaTeX code with the given options
+pub fn convert(input: &str, opts: &ConvertOptions) -> Converted {
+    convert_inner(input, opts)
+}
+
+/// Converts a syntax node already parsed, e.g. a part of a larger document
+///
+/// The node is converted in the mode of the options without re-parsing, so
+/// `expand_macros` takes no effect. Ranges in the result are relative to the
+/// tree containing the node, which can be mapped to the input by the
+/// [`InputMap`] of [`mitex_parser::parse_with_input_map`].
+pub fn convert_node(node: &SyntaxNode, opts: &ConvertOptions) -> Converted {
+    let spec = opts.spec.as_ref().unwrap_or(&DEFAULT_SPEC);
+    convert_node_inner(node.clone(), spec, opts)
+}
 
 pub fn convert_text(input: &str, spec: Option<CommandSpec>) -> Result<String, String> {
-    convert_inner(input, LaTeXMode::Text, spec, parse)
+    convert(input, &ConvertOptions::new(LaTeXMode::Text).spec(spec)).into_result()
 }
 
 pub fn convert_math(input: &str, spec: Option<CommandSpec>) -> Result<String, String> {
-    convert_inner(input, LaTeXMode::Math, spec, parse)
+    convert(input, &ConvertOptions::new(LaTeXMode::Math).spec(spec)).into_result()
+}
+
+/// Converts text mode LaTeX code, reporting problems as [`Diagnostic`]s
+pub fn convert_text_with_diagnostics(input: &str, spec: Option<CommandSpec>) -> Converted {
+    convert(input, &ConvertOptions::new(LaTeXMode::Text).spec(spec))
+}
+
+/// Converts math mode LaTeX code, reporting problems as [`Diagnostic`]s
+pub fn convert_math_with_diagnostics(input: &str, spec: Option<CommandSpec>) -> Converted {
+    convert(input, &ConvertOptions::new(LaTeXMode::Math).spec(spec))
+}
+
+/// Converts text mode LaTeX code, continuing after errors
+///
+/// Elements failed to convert are replaced by `#mitex-error("..")`
+/// placeholders, and all problems are reported as [`Diagnostic`]s.
+pub fn convert_text_recovering(input: &str, spec: Option<CommandSpec>) -> Converted {
+    convert(
+        input,
+        &ConvertOptions::new(LaTeXMode::Text)
+            .spec(spec)
+            .recover(true),
+    )
+}
+
+/// Converts math mode LaTeX code, continuing after errors
+///
+/// See [`convert_text_recovering`] for details.
+pub fn convert_math_recovering(input: &str, spec: Option<CommandSpec>) -> Converted {
+    convert(
+        input,
+        &ConvertOptions::new(LaTeXMode::Math)
+            .spec(spec)
+            .recover(true),
+    )
+}
+
+/// Converts text mode LaTeX code along with a source map from the output to
+/// the input
+///
+/// If `recover` is true, continues converting after errors like
+/// [`convert_text_recovering`].
+pub fn convert_text_with_source_map(
+    input: &str,
+    spec: Option<CommandSpec>,
+    recover: bool,
+) -> Converted {
+    convert(
+        input,
+        &ConvertOptions::new(LaTeXMode::Text)
+            .spec(spec)
+            .recover(recover)
+            .source_map(true),
+    )
+}
+
+/// Converts math mode LaTeX code along with a source map from the output to
+/// the input
+///
+/// See [`convert_text_with_source_map`] for details.
+pub fn convert_math_with_source_map(
+    input: &str,
+    spec: Option<CommandSpec>,
+    recover: bool,
+) -> Converted {
+    convert(
+        input,
+        &ConvertOptions::new(LaTeXMode::Math)
+            .spec(spec)
+            .recover(recover)
+            .source_map(true),
+    )
 }
 
 /// For internal testing
 pub fn convert_math_no_macro(input: &str, spec: Option<CommandSpec>) -> Result<String, String> {
-    convert_inner(input, LaTeXMode::Math, spec, parse_without_macro)
+    let opts = ConvertOptions::new(LaTeXMode::Math)
+        .spec(spec)
+        .expand_macros(false);
+    convert(input, &opts).into_result()
 }
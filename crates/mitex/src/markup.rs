@@ -0,0 +1,54 @@
+//! Escaping of text-mode words for Typst markup
+//!
+//! The punctuation lexed as separate tokens, e.g. `*`, `_`, `@` and `/`, is
+//! escaped by the converter itself. A word may still contain characters
+//! starting markup syntax, some of which are only special at the start of a
+//! line, e.g. `=` of a heading, `-` of a list, or `1.` of an enumeration.
+
+/// Writes a word in text mode to Typst markup, escaping only the characters
+/// which would start markup syntax at that place
+pub(crate) fn write_word(f: &mut String, word: &str) {
+    // `=` of a heading, `-` or `+` of a list, and `1.` of an enumeration
+    let is_marker = matches!(word, "-" | "+")
+        || (!word.is_empty() && word.bytes().all(|c| c == b'='))
+        || word
+            .strip_suffix('.')
+            .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|c| c.is_ascii_digit()));
+    if is_marker && at_line_start(f) {
+        match word.strip_suffix('.') {
+            Some(digits) => {
+                f.push_str(digits);
+                f.push_str("\\.");
+            }
+            None => {
+                f.push('\\');
+                f.push_str(word);
+            }
+        }
+        return;
+    }
+
+    let mut rest = word;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            // a label like `<tag>`, even if it is not closed
+            '<' if rest.starts_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '-')) => {
+                f.push_str("\\<")
+            }
+            // the soft hyphen `-?`
+            '-' if rest.starts_with('?') => {
+                f.push_str("-\\?");
+                rest = &rest[1..];
+            }
+            c => f.push(c),
+        }
+    }
+}
+
+/// Whether the markup written so far ends at the start of a line or of a
+/// content block, where the markers like `=` or `-` take effect
+fn at_line_start(f: &str) -> bool {
+    let line = f.rsplit('\n').next().unwrap_or_default().trim_end();
+    line.trim_start().is_empty() || (line.ends_with('[') && !line.ends_with("\\["))
+}
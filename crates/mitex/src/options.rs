@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use mitex_parser::spec::CommandSpec;
+
+use crate::converter::LaTeXMode;
+use crate::document::DocumentTemplate;
+use crate::handler::{CommandHandler, EnvHandler, Handlers};
+
+/// How the output of a math mode conversion is wrapped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MathStyle {
+    /// Writes the bare math code, e.g. `alpha`
+    #[default]
+    Bare,
+    /// Wraps the code as an inline equation, e.g. `$alpha$`
+    Inline,
+    /// Wraps the code as a block equation, e.g. `$ alpha $`
+    Display,
+}
+
+/// What to do with a command that is not found in the command spec
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownCommandPolicy {
+    /// Reports an error
+    #[default]
+    Error,
+    /// Writes the command name without the leading `\` and reports a warning
+    Keep,
+    /// Drops the command and reports a warning
+    Ignore,
+}
+
+/// Options for converting LaTeX code
+///
+/// ```
+/// use mitex::{ConvertOptions, LaTeXMode, MathStyle};
+///
+/// let opts = ConvertOptions::new(LaTeXMode::Math).math_style(MathStyle::Inline);
+/// assert_eq!(mitex::convert(r"\alpha", &opts).output, "$alpha $");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConvertOptions {
+    pub(crate) mode: LaTeXMode,
+    pub(crate) spec: Option<CommandSpec>,
+    pub(crate) math_style: MathStyle,
+    pub(crate) emit_labels: bool,
+    pub(crate) emit_prelude: bool,
+    pub(crate) document: bool,
+    pub(crate) templates: HashMap<String, DocumentTemplate>,
+    pub(crate) unknown_command: UnknownCommandPolicy,
+    pub(crate) expand_macros: bool,
+    pub(crate) recover: bool,
+    pub(crate) source_map: bool,
+    pub(crate) bibliography_file: String,
+    pub(crate) handlers: Handlers,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self::new(LaTeXMode::Text)
+    }
+}
+
+impl ConvertOptions {
+    /// Creates options converting in the given mode
+    pub fn new(mode: LaTeXMode) -> Self {
+        Self {
+            mode,
+            spec: None,
+            math_style: MathStyle::default(),
+            emit_labels: true,
+            emit_prelude: false,
+            document: false,
+            templates: DocumentTemplate::builtin(),
+            unknown_command: UnknownCommandPolicy::default(),
+            expand_macros: true,
+            recover: false,
+            source_map: false,
+            bibliography_file: "bibliography.yml".to_owned(),
+            handlers: Handlers::default(),
+        }
+    }
+
+    /// Sets the mode of the input, text mode by default
+    pub fn mode(mut self, mode: LaTeXMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the command spec, the default spec is used if it is `None`
+    pub fn spec(mut self, spec: Option<CommandSpec>) -> Self {
+        self.spec = spec;
+        self
+    }
+
+    /// Sets how the output is wrapped in math mode, bare code by default
+    pub fn math_style(mut self, math_style: MathStyle) -> Self {
+        self.math_style = math_style;
+        self
+    }
+
+    /// Whether to write `\label`s as Typst labels, true by default
+    pub fn emit_labels(mut self, emit_labels: bool) -> Self {
+        self.emit_labels = emit_labels;
+        self
+    }
+
+    /// Whether to prepend the `#import` of the MiTeX package and the
+    /// definitions of the used aliases, false by default
+    ///
+    /// The prelude makes the output a standalone Typst document.
+    pub fn emit_prelude(mut self, emit_prelude: bool) -> Self {
+        self.emit_prelude = emit_prelude;
+        self
+    }
+
+    /// Whether to convert the input as a full document, false by default
+    ///
+    /// Only the body of the `document` environment is converted if there is
+    /// one, and anything outside it is dropped. The preamble is read for the
+    /// declarations like `\newtheorem` and the metadata like `\title`, see
+    /// [`crate::Converted::metadata`]. The output starts with a
+    /// `#set document(..)` rule if the title, the authors or the keywords are
+    /// given, followed by the `#set page(..)`, `#set par(..)` and
+    /// `#set text(..)` rules of the layout set in the preamble, e.g. by
+    /// geometry and babel. The template of the class may override the layout,
+    /// e.g. the margins of `IEEEtran`.
+    pub fn document(mut self, document: bool) -> Self {
+        self.document = document;
+        self
+    }
+
+    /// Maps the document class `class` to a Typst template in the document
+    /// mode, replacing the existing mapping
+    ///
+    /// The body is wrapped by the template of its class, with the metadata and
+    /// the options of the class as the arguments, see [`DocumentTemplate`].
+    /// The classes `article`, `report` and `IEEEtran` are mapped to the
+    /// built-in templates `mitex-article`, `mitex-report` and `mitex-ieee` by
+    /// default.
+    pub fn template(mut self, class: impl Into<String>, template: DocumentTemplate) -> Self {
+        self.templates.insert(class.into(), template);
+        self
+    }
+
+    /// Sets what to do with unknown commands, an error by default
+    pub fn unknown_command(mut self, policy: UnknownCommandPolicy) -> Self {
+        self.unknown_command = policy;
+        self
+    }
+
+    /// Whether to expand the macros defined by `\newcommand` etc., true by
+    /// default
+    pub fn expand_macros(mut self, expand_macros: bool) -> Self {
+        self.expand_macros = expand_macros;
+        self
+    }
+
+    /// Whether to continue converting after errors, false by default
+    ///
+    /// Elements failed to convert are replaced by `#mitex-error("..")`
+    /// placeholders, and all problems are reported as diagnostics.
+    pub fn recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Whether to produce a source map from the output to the input, false by
+    /// default
+    pub fn source_map(mut self, source_map: bool) -> Self {
+        self.source_map = source_map;
+        self
+    }
+
+    /// Sets the Hayagriva file loaded by the converted `thebibliography`
+    /// environments, `bibliography.yml` by default
+    ///
+    /// The entries are not written to the file by the converter but returned
+    /// in [`crate::Converted::bib_items`].
+    pub fn bibliography_file(mut self, path: impl Into<String>) -> Self {
+        self.bibliography_file = path.into();
+        self
+    }
+
+    /// Sets the handlers for commands and environments, the built-in ones by
+    /// default
+    pub fn handlers(mut self, handlers: Handlers) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    /// Registers a handler for the command `\name`, replacing the existing
+    /// one
+    pub fn command_handler(
+        mut self,
+        name: impl Into<String>,
+        handler: impl CommandHandler + 'static,
+    ) -> Self {
+        self.handlers.register_command(name, handler);
+        self
+    }
+
+    /// Registers a handler for the environment `name`, replacing the existing
+    /// one
+    pub fn env_handler(
+        mut self,
+        name: impl Into<String>,
+        handler: impl EnvHandler + 'static,
+    ) -> Self {
+        self.handlers.register_env(name, handler);
+        self
+    }
+}
@@ -0,0 +1,51 @@
+use mitex_parser::spec::{CmdShape, CommandSpec, CommandSpecItem, EnvShape};
+
+/// Names defined in Typst, which must not be shadowed by the prelude
+const BUILTIN_NAMES: &[&str] = &["and", "or", "in", "not"];
+
+/// Generates a Typst prelude importing the MiTeX package and defining the
+/// aliases in the spec, so that the text mode output can be compiled as a
+/// standalone document
+///
+/// The `helpers` in mitex-scope referred by the output, e.g. `mitex-error` for
+/// placeholders, are also defined.
+pub fn typst_prelude<'a>(
+    spec: &'a CommandSpec,
+    helpers: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut aliases = std::collections::BTreeSet::<&str>::new();
+    for (_, cmd) in spec.items() {
+        let alias = match cmd {
+            CommandSpecItem::Cmd(CmdShape {
+                alias: Some(alias), ..
+            }) => alias.as_str(),
+            CommandSpecItem::Env(EnvShape {
+                alias: Some(alias), ..
+            }) => alias.as_str(),
+            _ => continue,
+        };
+        if alias.is_empty() || !alias.chars().all(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+        if BUILTIN_NAMES.contains(&alias) {
+            continue;
+        }
+        aliases.insert(alias);
+    }
+
+    let mut aliases = aliases.into_iter().collect::<Vec<_>>();
+    aliases.extend(helpers);
+    let defs = aliases
+        .into_iter()
+        .map(|alias| format!(r#"#let {alias} = mitex-scope.at("{alias}", default: none);"#))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        r#"
+#import "@preview/mitex:{}": *
+{defs}
+
+"#,
+        env!("CARGO_PKG_VERSION")
+    )
+}
@@ -0,0 +1,15 @@
+use std::ops::Range;
+
+/// A mapping from a range of the converted Typst code to the range of the
+/// LaTeX code it is converted from
+///
+/// Like [`crate::Diagnostic`], the input range is a byte range into the LaTeX
+/// input, or into the text of the syntax tree for [`crate::convert_node`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceMapping {
+    /// The byte range in the output
+    pub output: Range<usize>,
+    /// The byte range in the input
+    pub input: Range<usize>,
+}
@@ -0,0 +1,50 @@
+//! TeX input conventions of text mode, e.g. ``` ``quoted'' ``` and `--`,
+//! which are converted to the Unicode characters they stand for
+
+use mitex_parser::syntax::{SyntaxKind, SyntaxToken};
+
+/// Writes a word in text mode with the ligatures replaced
+///
+/// ```text
+/// `` -> “    ` -> ‘    -- -> –    --- -> —    ?` -> ¿    !` -> ¡
+/// ```
+pub(crate) fn write_word(f: &mut String, word: &str) {
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' if chars.next_if_eq(&'`').is_some() => f.push('“'),
+            '`' => f.push('‘'),
+            '-' if chars.next_if_eq(&'-').is_some() => match chars.next_if_eq(&'-') {
+                Some(_) => f.push('—'),
+                None => f.push('–'),
+            },
+            '?' if chars.next_if_eq(&'`').is_some() => f.push('¿'),
+            '!' if chars.next_if_eq(&'`').is_some() => f.push('¡'),
+            c => f.push(c),
+        }
+    }
+}
+
+/// The quote written for an apostrophe in text mode, where a pair of
+/// apostrophes like `''` is a closing double quote
+///
+/// The apostrophes are split into separate tokens by the lexer, so the
+/// first one of a pair becomes `”` and the second one becomes nothing.
+pub(crate) fn apostrophe_quote(token: &SyntaxToken) -> &'static str {
+    let is_apostrophe = |token: Option<SyntaxToken>| {
+        token.is_some_and(|token| token.kind() == SyntaxKind::TokenApostrophe)
+    };
+    let mut preceding = 0;
+    let mut prev = token.prev_token();
+    while is_apostrophe(prev.clone()) {
+        preceding += 1;
+        prev = prev.and_then(|token| token.prev_token());
+    }
+    if preceding % 2 == 1 {
+        ""
+    } else if is_apostrophe(token.next_token()) {
+        "”"
+    } else {
+        "’"
+    }
+}
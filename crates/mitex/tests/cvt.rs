@@ -60,6 +60,52 @@ mod cvt {
 
     #[cfg(test)]
     mod misc;
+
+    #[cfg(test)]
+    mod diagnostics;
+
+    #[cfg(test)]
+    mod source_map;
+
+    #[cfg(test)]
+    mod spec;
+
+    #[cfg(test)]
+    mod options;
+
+    #[cfg(test)]
+    mod handler;
+
+    #[cfg(test)]
+    mod section;
+
+    #[cfg(test)]
+    mod reference;
+
+    #[cfg(test)]
+    mod citation;
+
+    #[cfg(test)]
+    mod align;
+
+    #[cfg(test)]
+    mod verbatim;
+
+    #[cfg(test)]
+    mod color;
+
+    #[cfg(test)]
+    mod list;
+
+    #[cfg(test)]
+    mod link;
+
+    #[cfg(test)]
+    mod theorem;
+
+    #[cfg(test)]
+    mod document;
+
     /// Convenient function to launch/debug a test case
     #[test]
     fn bug_playground() {}
@@ -83,7 +129,7 @@ mod cvt {
 
     #[test]
     fn test_normal() {
-        assert_snapshot!(convert_math(r#"\int_1^2 x \mathrm{d} x"#).unwrap(), @"integral _(1 )^(2 ) x  upright(d ) x ");
+        assert_snapshot!(convert_math(r#"\int_1^2 x \mathrm{d} x"#).unwrap(), @r###"integral _(1 )^(2 ) x  upright("d") x"###);
     }
 
     #[test]
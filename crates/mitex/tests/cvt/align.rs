@@ -0,0 +1,111 @@
+use super::prelude::*;
+
+#[test]
+fn test_align_rows() {
+    assert_snapshot!(convert_text(r#"\begin{align}
+a &= b \label{eq:a} \\
+c &= d \notag \\
+e &= f \tag{1a} \\
+g &= h \nonumber
+\end{align}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a$, $=  b$, ), label: <eq:a>),
+      (cells: ($c$, $=  d$, ), numbered: false),
+      (cells: ($e$, $=  f$, ), tag: [(1a)]),
+      (cells: ($g$, $=  h$, ), numbered: false),
+    );
+    "###);
+}
+
+#[test]
+fn test_align_unnumbered() {
+    assert_snapshot!(convert_text(r#"\begin{align*}
+a &= b \\[2pt]
+c &= d \\
+\end{align*}"#).unwrap(), @r###"#math.equation(block: true, numbering: none, $ a & =  b \ c & =  d $);"###);
+    assert_snapshot!(convert_text(r#"\begin{align*}
+a &= b \tag*{A}
+\end{align*}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a$, $=  b$, ), tag: [A]),
+    );
+    "###);
+}
+
+#[test]
+fn test_align_intertext() {
+    assert_snapshot!(convert_text(r#"\begin{align}
+a &= b \\
+\intertext{and so}
+c &= d
+\end{align}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a$, $=  b$, )),
+      (intertext: [and so]),
+      (cells: ($c$, $=  d$, )),
+    );
+    "###);
+}
+
+#[test]
+fn test_align_variants() {
+    assert_snapshot!(convert_text(r#"\begin{gather}
+a = b \\ c = d
+\end{gather}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a  =  b$, )),
+      (cells: ($c  =  d$, )),
+      aligns: (center,),
+    );
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{multline}
+a + b \\ + c \label{eq:m}
+\end{multline}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a  +  b$, ), numbered: false),
+      (cells: ($+  c$, ), label: <eq:m>),
+      aligns: (center,),
+    );
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{eqnarray}
+a & = & b
+\end{eqnarray}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a$, $=$, $b$, )),
+      aligns: (right, center, left),
+    );
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{alignat}{2}
+a &= b & c &= d
+\end{alignat}"#).unwrap(), @r###"
+    #mitex-align(
+      (cells: ($a$, $=  b$, $c$, $=  d$, )),
+    );
+    "###);
+}
+
+#[test]
+fn test_align_in_math() {
+    assert_snapshot!(convert_math(r#"\begin{align}
+a &= b \tag{1} \\
+\intertext{and}
+c &= d \notag
+\end{align}"#).unwrap(), @r###"
+    aligned(
+    a  &=  b  mitexlabel(1 ) \ 
+    #[and] \ 
+    c  &=  d   
+    )
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{equation}
+\begin{split}
+a &= b \\ &= c
+\end{split}
+\end{equation}"#).unwrap(), @r###"
+    $ aligned(
+    aligned(
+    a  &=  b  \  &=  c 
+    )
+    ) $
+    "###);
+}
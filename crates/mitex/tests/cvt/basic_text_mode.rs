@@ -3,7 +3,7 @@ use super::prelude::*;
 #[test]
 fn test_convert_text_mode() {
     assert_snapshot!(convert_text(r#"abc"#).unwrap(), @"abc");
-    assert_snapshot!(convert_text(r#"\section{Title}"#).unwrap(), @"#heading(level: 1)[Title];");
+    assert_snapshot!(convert_text(r#"\section{Title}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[Title];"###);
     assert_snapshot!(convert_text(r#"a \textbf{strong} text"#).unwrap(), @"a #strong[strong]; text");
     assert_snapshot!(convert_text(r###"\section{Title}
 
@@ -14,15 +14,51 @@ fn test_convert_text_mode() {
     \begin{equation}
       a^2 + b^2 = c^2 \label{eq:pythagoras}
     \end{equation}"###).unwrap(), @r###"
-
-    #heading(level: 1)[Title];
+    #set heading(numbering: "1.1");#heading(level: 1)[Title];
 
     A #strong[strong]; text\, a #emph[emph]; text and inline equation #math.equation(block: false, $x  +  y $);.
 
-    Also block #mitexref[eq:pythagoras];.
+    Also block (#ref(<eq:pythagoras>, supplement: none)).
 
     $ aligned(
     a ^(2 ) +  b ^(2 ) =  c ^(2 ) 
     ) $<eq:pythagoras>
     "###);
 }
+
+#[test]
+fn test_text_ligatures() {
+    assert_snapshot!(convert_text(r#"``Quoted'' and `single', don't.  Pages 1--2---or ?`Qué? !`Olé!"#).unwrap(), @r###"“Quoted” and ‘single’\, don’t.  Pages 1–2—or ¿Qué? ¡Olé!"###);
+    assert_snapshot!(convert_text(r#"Mr.~Smith\,et al.\ldots{} and NASA\@. Then"#).unwrap(), @"Mr.~Smith et al.… and NASA. Then");
+    assert_snapshot!(convert_text(r#"$f'' = a--b \ldots \,$"#).unwrap(), @"#math.equation(block: false, $f '' =  a - - b  dots.h  thin $);");
+}
+
+#[test]
+fn test_markup_escape() {
+    // markers at the start of a line
+    assert_snapshot!(convert_text("= result\n== two\n- item\n+ 1\n1. first\n12. second").unwrap(), @r###"
+    \= result
+    \== two
+    \- item
+    \+ 1
+    1\. first
+    12\. second
+    "###);
+    // markers in the middle of a line
+    assert_snapshot!(convert_text("a - b = c + 1. and 1.5 or -1 == x").unwrap(), @"a - b = c + 1. and 1.5 or -1 == x");
+    // markers at the start of a content block
+    assert_snapshot!(convert_text(r#"\textbf{- a} \emph{= b} \section{1. Intro}"#).unwrap(), @r###"#strong[\- a]; #emph[\= b]; #set heading(numbering: "1.1");#heading(level: 1)[1\. Intro];"###);
+    // labels, references, strong text and comments
+    assert_snapshot!(convert_text(r#"<tag> a<b a<-b a < b @user *x* _y_ //c /*d*/ x-?y #let [z]"#).unwrap(), @r###"\<tag> a\<b a\<-b a < b \@user \*x\* \_y\_ \/\/c \/\*d\*\/ x-\?y \#let \[z\]"###);
+}
+
+#[test]
+fn test_text_fonts() {
+    assert_snapshot!(convert_text(r#"\emph{a \emph{b}} \texttt{c} \textsc{d} \textsf{e} \underline{f} x\textsuperscript{2} H\textsubscript{2}O"#).unwrap(), @r###"#emph[a #emph[b];]; #text(font: "DejaVu Sans Mono")[c]; #smallcaps[d]; #text(font: "New Computer Modern Sans")[e]; #underline[f]; x#super[2]; H#sub[2];O"###);
+    // declarations are scoped to the enclosing group
+    assert_snapshot!(convert_text(r#"{\bf a \it b} c {\em d} \bfseries e"#).unwrap(), @"#strong[a #emph[b];]; c #emph[d]; #strong[e];");
+    assert_snapshot!(convert_text(r#"\large Title {\tiny small} \Huge big"#).unwrap(), @"#text(size: 1.2em)[Title #text(size: 0.417em)[small]; #text(size: 2.073em)[big];];");
+    // sizes are relative to the base size when nested
+    assert_snapshot!(convert_text(r#"\large a \Huge b {\normalsize c \tiny d}"#).unwrap(), @"#text(size: 1.2em)[a #text(size: 2.073em)[b #text(size: 0.402em)[c #text(size: 0.5em)[d];];];];");
+    assert_snapshot!(convert_math(r#"\bf x \texttt{y}"#).unwrap(), @"mitexbold( x  #texttt[y];)");
+}
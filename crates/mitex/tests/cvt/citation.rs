@@ -0,0 +1,70 @@
+use super::prelude::*;
+
+#[test]
+fn test_cite_forms() {
+    assert_snapshot!(convert_text(r#"\cite{knuth84} \citep{knuth84} \parencite{knuth84}"#).unwrap(), @"#cite(<knuth84>); #cite(<knuth84>); #cite(<knuth84>);");
+    assert_snapshot!(convert_text(r#"\citet{knuth84} \textcite{knuth84}"#).unwrap(), @r###"#cite(<knuth84>, form: "prose"); #cite(<knuth84>, form: "prose");"###);
+    assert_snapshot!(convert_text(r#"\citeauthor{knuth84} \citeyear{knuth84}"#).unwrap(), @r###"#cite(<knuth84>, form: "author"); #cite(<knuth84>, form: "year");"###);
+    assert_snapshot!(convert_text(r#"\nocite{knuth84}"#).unwrap(), @"#cite(<knuth84>, form: none);");
+}
+
+#[test]
+fn test_cite_notes() {
+    assert_snapshot!(convert_text(r#"\cite[p. 5]{knuth84}"#).unwrap(), @"#cite(<knuth84>, supplement: [p. 5]);");
+    assert_snapshot!(convert_text(r#"\citep[see][chap. 2]{knuth84}"#).unwrap(), @"see #cite(<knuth84>, supplement: [chap. 2]);");
+    assert_snapshot!(convert_text(r#"\citep[][p. 5]{knuth84}"#).unwrap(), @"#cite(<knuth84>, supplement: [p. 5]);");
+}
+
+#[test]
+fn test_cite_keys() {
+    assert_snapshot!(convert_text(r#"\cite{knuth84, lamport94}[1]"#).unwrap(), @r###"#cite(<knuth84>)#cite(<lamport94>);\[1\]"###);
+    assert_snapshot!(convert_text(r#"\cite[p. 5]{a,b}"#).unwrap(), @"#cite(<a>)#cite(<b>, supplement: [p. 5]);");
+    assert_snapshot!(convert_text(r#"\cite{doe/2020}"#).unwrap(), @r###"#cite(label("doe/2020"));"###);
+}
+
+#[test]
+fn test_bibliography() {
+    assert_snapshot!(convert_text(r#"\bibliographystyle{plain}
+\bibliography{refs}"#).unwrap(), @r###"
+
+    #bibliography("refs.bib", style: "ieee");
+    "###);
+    assert_snapshot!(convert_text(r#"\nocite{*}
+\bibliography{refs,more.bib}
+\bibliographystyle{plainnat}"#).unwrap(), @r###"
+
+    #bibliography(("refs.bib", "more.bib"), style: "chicago-author-date", full: true);
+    "###);
+    assert_snapshot!(convert_text(r#"\addbibresource{refs.bib}
+\printbibliography"#).unwrap(), @r###"
+
+    #bibliography("refs.bib");
+    "###);
+}
+
+#[test]
+fn test_thebibliography() {
+    let converted = mitex::convert(
+        r#"See \cite{knuth84}.
+\begin{thebibliography}{9}
+\bibitem{knuth84} D.~Knuth, \emph{The \TeX{}book}, 1984.
+\bibitem[Lam94]{lamport94}
+  L. Lamport, % comment
+  LaTeX \& friends.
+\end{thebibliography}"#,
+        &mitex::ConvertOptions::default().bibliography_file("main.bib.yml"),
+    );
+    assert_snapshot!(converted.output, @r###"
+    See #cite(<knuth84>);.
+    #bibliography("main.bib.yml");
+    "###);
+    assert_snapshot!(mitex::hayagriva_yaml(&converted.bib_items), @r###"
+    "knuth84":
+      type: misc
+      title: "D. Knuth, The TeXbook, 1984."
+    "lamport94":
+      type: misc
+      title: "L. Lamport, LaTeX & friends."
+    "###);
+    assert_eq!(converted.bib_items[1].label.as_deref(), Some("Lam94"));
+}
@@ -0,0 +1,30 @@
+use super::prelude::*;
+
+#[test]
+fn named() {
+    assert_snapshot!(convert_text(r#"a \textcolor{red}{b} {\color{teal} c d} e"#).unwrap(), @"a #text(fill: rgb(100%, 0%, 0%))[b]; #text(fill: rgb(0%, 50%, 50%))[c d]; e");
+    assert_snapshot!(convert_math(r#"x \textcolor{blue}{y} \color{gray} z"#).unwrap(), @"x  text(fill: #rgb(0%, 0%, 100%), y ) text(fill: #luma(50%), z )");
+}
+
+#[test]
+fn expression() {
+    assert_snapshot!(convert_text(r#"\textcolor{red!30}{a} \textcolor{red!30!blue}{b} \textcolor{red!50!blue!20}{c}"#).unwrap(), @"#text(fill: color.mix((rgb(100%, 0%, 0%), 30%), (luma(100%), 70%), space: rgb))[a]; #text(fill: color.mix((rgb(100%, 0%, 0%), 30%), (rgb(0%, 0%, 100%), 70%), space: rgb))[b]; #text(fill: color.mix((color.mix((rgb(100%, 0%, 0%), 50%), (rgb(0%, 0%, 100%), 50%), space: rgb), 20%), (luma(100%), 80%), space: rgb))[c];");
+}
+
+#[test]
+fn model() {
+    assert_snapshot!(convert_text(r#"\textcolor[rgb]{0.1,0.2,0.3}{a} \textcolor[HTML]{FF8800}{b} \color[gray]{0.5} c"#).unwrap(), @r###"#text(fill: rgb(10%, 20%, 30%))[a]; #text(fill: rgb("#FF8800"))[b]; #text(fill: luma(50%))[c];"###);
+    assert_snapshot!(convert_text(r#"\textcolor[RGB]{255, 136, 0}{a} \textcolor[cmyk]{0,1,0,0.5}{b}"#).unwrap(), @"#text(fill: rgb(255, 136, 0))[a]; #text(fill: cmyk(0%, 100%, 0%, 50%))[b];");
+}
+
+#[test]
+fn defined() {
+    assert_snapshot!(convert_text(r#"\definecolor{accent}{HTML}{1F77B4}\colorlet{light}{accent!20}\textcolor{accent}{a} \colorbox{light}{b}"#).unwrap(), @r###"#text(fill: rgb("#1F77B4"))[a]; #box(fill: color.mix((rgb("#1F77B4"), 20%), (luma(100%), 80%), space: rgb), inset: 3pt)[b];"###);
+    assert_snapshot!(convert_text(r#"\colorlet{c}{red}\begin{equation}\color{c} x\end{equation}"#).unwrap(), @"$ aligned(text(fill: #rgb(100%, 0%, 0%), x )) $");
+}
+
+#[test]
+fn color_box() {
+    assert_snapshot!(convert_text(r#"\colorbox{yellow}{a} \fcolorbox{red}{white}{b}"#).unwrap(), @"#box(fill: cmyk(0%, 0%, 100%, 0%), inset: 3pt)[a]; #box(fill: luma(100%), stroke: 0.4pt + rgb(100%, 0%, 0%), inset: 3pt)[b];");
+    assert_snapshot!(convert_math(r#"\fcolorbox{red}{white}{x^2}"#).unwrap(), @"#box(fill: luma(100%), stroke: 0.4pt + rgb(100%, 0%, 0%), inset: 3pt, $x ^(2 )$)");
+}
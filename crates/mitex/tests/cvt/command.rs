@@ -3,7 +3,7 @@ use super::prelude::*;
 #[test]
 fn test_starrd_command() {
     // Description: If the starred command is defined, it is treated as a starred
-    assert_snapshot!(convert_math(r#"\operatorname*{a}"#).unwrap(), @"operatornamewithlimits(a )"
+    assert_snapshot!(convert_math(r#"\operatorname*{a}"#).unwrap(), @r###"operatornamewithlimits("a")"###
     );
     // Description: If the starred command is not defined, it is treated as a normal
     // command
@@ -74,3 +74,13 @@ fn infix() {
     assert_snapshot!(convert_math(r#"a \over b"#).unwrap(), @"frac(a  , b )");
     assert_snapshot!(convert_math(r#"1 + {2 \over 3}"#).unwrap(), @"1  +  frac(2  , 3 )");
 }
+
+#[test]
+fn text_in_math() {
+    // Description: plain text arguments become strings
+    assert_snapshot!(convert_math(r#"\mathrm{d}x + \operatorname{arg\,max}_x + \mathrm{a"b\#\textbackslash{}}"#).unwrap(), @r###"upright("d")x  +  operatorname("arg max")_(x ) +  upright("a\"b#\\")"###);
+    // Description: other arguments are converted in math mode
+    assert_snapshot!(convert_math(r#"\mathrm{\alpha x} \mathrm x"#).unwrap(), @"upright(alpha  x ) upright(x )");
+    // Description: text is escaped, and inline math switches back to math mode
+    assert_snapshot!(convert_math(r#"\text{a "b" \# \$5 50\% $x^2$ and \(y\)}"#).unwrap(), @r###"#textmath[a \"b\" \# \$5 50% #math.equation(block: false, $x ^(2 )$); and #math.equation(block: false, $y $);];"###);
+}
@@ -0,0 +1,245 @@
+use super::prelude::*;
+
+fn diagnostics(converted: &mitex::Converted) -> String {
+    converted
+        .diagnostics
+        .iter()
+        .map(|d| format!("{} {} {:?}: {}", d.severity, d.code, d.range, d.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn text_diagnostics(input: &str) -> String {
+    diagnostics(&mitex::convert_text_with_diagnostics(
+        input,
+        Some(DEFAULT_SPEC.clone()),
+    ))
+}
+
+fn math_diagnostics(input: &str) -> String {
+    diagnostics(&mitex::convert_math_with_diagnostics(
+        input,
+        Some(DEFAULT_SPEC.clone()),
+    ))
+}
+
+#[test]
+fn test_unknown_command() {
+    assert_snapshot!(math_diagnostics(r#"a + \unknown"#), @r###"error unknown-command 4..12: unknown command: \unknown"###);
+    assert_snapshot!(math_diagnostics(r#"a + \unknown{x}"#), @r###"error unknown-command 4..12: unknown command: \unknown"###);
+}
+
+#[test]
+fn test_unknown_environment() {
+    assert_snapshot!(text_diagnostics(r#"a \begin{unknown}x\end{unknown}"#), @r###"error unknown-environment 2..31: unknown environment: \unknown"###);
+}
+
+#[test]
+fn test_item_outside_list() {
+    assert_snapshot!(text_diagnostics(r#"a \item b"#), @"error item-outside-list 2..7: item command outside of itemize, enumerate or description");
+}
+
+#[test]
+fn test_missing_argument() {
+    assert_snapshot!(text_diagnostics(r#"see \ref"#), @"error missing-argument 4..8: missing label of reference command");
+    assert_snapshot!(recover_text(r#"a \label"#), @r###"
+    a #mitex-error("missing name of label command");
+    ---
+    error missing-argument 2..8: missing name of label command
+    "###);
+    assert_snapshot!(recover_text(r#"\section{A}\label"#), @r###"
+    #set heading(numbering: "1.1");#heading(level: 1)[A];#mitex-error("missing name of label command");
+    ---
+    error missing-argument 11..17: missing name of label command
+    "###);
+    assert_snapshot!(recover_text(r#"\begin{equation}x\label\end{equation}"#), @r###"
+    $ aligned(x #mitex-error("missing name of label command")) $
+    ---
+    error missing-argument 17..23: missing name of label command
+    "###);
+    assert_snapshot!(recover_text(r#"\begin{align}x\label\end{align}"#), @r###"
+    #mitex-error("missing name of label command");
+    ---
+    error missing-argument 14..20: missing name of label command
+    "###);
+    assert_snapshot!(convert_text(r#"a \label x b"#).unwrap(), @"a <x> b");
+    assert_snapshot!(recover_text(r#"a \includegraphics"#), @r###"
+    a #mitex-error("missing file of includegraphics command");
+    ---
+    error missing-argument 2..18: missing file of includegraphics command
+    "###);
+    assert_snapshot!(recover_text(r#"\begin{figure}x\caption"#), @r###"
+    #mitex-error("missing text of caption command");
+    ---
+    error missing-argument 15..23: missing text of caption command
+    "###);
+    assert_snapshot!(recover_text(r#"{\eqref} and \cref"#), @r###"
+    #mitex-error("missing label of reference command"); and #mitex-error("missing label of reference command");
+    ---
+    error missing-argument 1..7: missing label of reference command
+    error missing-argument 13..18: missing label of reference command
+    "###);
+}
+
+#[test]
+fn test_unknown_alignment() {
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{lx|}a\end{tabular}"#), @"error unknown-alignment 17..18: unknown alignment: x");
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{l@{ -- }p{2\parindent}>{\color{red}}c}a\end{tabular}"#), @r###"
+    warning unsupported 19..23: ignored column specifier: @{ -- }
+    warning unsupported 26..37: unsupported column width: 2\parindent
+    warning unsupported 40..51: unsupported column declaration: \color{red}
+    "###);
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{*{257}{c}}a\end{tabular}"#), @"error unknown-alignment 16..17: invalid repeat count: 257");
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{*{256}{*{256}{*{256}{|}}}}a\end{tabular}"#), @"error unknown-alignment 30..31: invalid repeat count: 256");
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{*{200}{*{200}{c}}}a\end{tabular}"#), @"error unknown-alignment 23..24: invalid repeat count: 200");
+    assert_snapshot!(text_diagnostics(r#"\begin{tabular}{p}a\end{tabular}"#), @"error unknown-alignment 16..17: missing argument of column specifier: p");
+}
+
+#[test]
+fn test_error_token() {
+    assert_snapshot!(math_diagnostics(r#"a \end{}"#), @r###"error unexpected-token 2..2: error unexpected: """###);
+    assert_snapshot!(math_diagnostics(r#"\left{.}a\right{.}"#), @r###"error unexpected-token 7..8: error unexpected: "}""###);
+}
+
+#[test]
+fn test_invalid_formula() {
+    assert_snapshot!(text_diagnostics(r#"a $b"#), @"error invalid-formula 2..4: formula is not valid");
+}
+
+#[test]
+fn test_partial_output() {
+    let converted = mitex::convert_text_with_diagnostics(r#"a \unknown b"#, None);
+    assert!(converted.has_errors());
+    assert_snapshot!(converted.output, @"a");
+}
+
+fn recover_text(input: &str) -> String {
+    let converted = mitex::convert_text_recovering(input, Some(DEFAULT_SPEC.clone()));
+    format!("{}\n---\n{}", converted.output, diagnostics(&converted))
+}
+
+fn recover_math(input: &str) -> String {
+    let converted = mitex::convert_math_recovering(input, Some(DEFAULT_SPEC.clone()));
+    format!("{}\n---\n{}", converted.output, diagnostics(&converted))
+}
+
+#[test]
+fn test_recover_all_errors() {
+    assert_snapshot!(recover_math(r#"a + \unknown + \foo{x} + \alpha"#), @r###"
+    a  +  #mitex-error("unknown command: \\unknown") +  #mitex-error("unknown command: \\foo")x  +  alpha 
+    ---
+    error unknown-command 4..12: unknown command: \unknown
+    error unknown-command 15..19: unknown command: \foo
+    "###);
+    assert_snapshot!(recover_math(r#"\left{.}a\right{.} + b"#), @r###"
+    lr( . #mitex-error("error unexpected: \"}\"")a #mitex-error("unknown command: \\right").  +  b 
+    ---
+    error unexpected-token 7..8: error unexpected: "}"
+    error unknown-command 9..15: unknown command: \right
+    "###);
+    assert_snapshot!(recover_text(r#"a \item b \begin{unknown}c\end{unknown} $d"#), @r###"
+    a #mitex-error("item command outside of itemize, enumerate or description"); b #mitex-error("unknown environment: \\unknown"); #mitex-error("formula is not valid");
+    ---
+    error item-outside-list 2..7: item command outside of itemize, enumerate or description
+    error unknown-environment 10..39: unknown environment: \unknown
+    error invalid-formula 40..42: formula is not valid
+    "###);
+}
+
+#[test]
+fn test_recover_escape_message() {
+    assert_snapshot!(recover_text(r#"\"a"#), @r###"
+    #mitex-error("unknown command: \\\"");a
+    ---
+    error unknown-command 0..2: unknown command: \"
+    "###);
+}
+
+#[test]
+fn longtable_repeated_header() {
+    assert_snapshot!(text_diagnostics(r###"\begin{longtable}{l}
+    a \\ \endfirsthead
+    b \\ \endhead
+\end{longtable}"###), @r###"warning unsupported 53..61: ignored rows of \endhead, repeating the rows of \endfirsthead instead"###);
+    assert_snapshot!(text_diagnostics(r###"\begin{tabularx}{\parindent}{X}a\end{tabularx}"###), @r###"warning unsupported 16..28: unsupported table width: \parindent"###);
+}
+
+#[test]
+fn table_spans_and_rules() {
+    assert_snapshot!(recover_text(r###"\begin{tabular}{cc}\multicolumn{4000000000}{c}{x} \\ \multirow{-300}{*}{y} & z\end{tabular}"###), @r###"
+    #table(stroke: none,
+    columns: 2,
+    align: (center, center, ),
+
+    table.cell(colspan: 256, align: center)[x],
+    table.cell(rowspan: 256)[y], [z],
+    );
+    ---
+    warning unsupported 31..43: span count above 256: 4000000000
+    warning unsupported 62..68: span count above 256: 300
+    "###);
+    assert_snapshot!(recover_text(r###"\begin{tabular}{ccc}\cline{3-1} a & b & c \\ \cmidrule{0-2}\end{tabular}"###), @r###"
+    #table(stroke: none,
+    columns: 3,
+    align: (center, center, center, ),
+
+    [a ], [b ], [c ],
+    );
+    ---
+    warning unsupported 20..31: ignored rule of invalid columns: 3-1
+    warning unsupported 45..59: ignored rule of invalid columns: 0-2
+    "###);
+}
+
+#[test]
+fn array_border_rules() {
+    assert_snapshot!(text_diagnostics(r###"$\begin{array}{|c|}\hline a \\ \hline\end{array}$"###), @r###"
+    warning unsupported 14..19: ignored rule at the border of matrix
+    warning unsupported 19..25: ignored rule at the border of matrix
+    warning unsupported 31..37: ignored rule at the border of matrix
+    "###);
+}
+
+#[test]
+fn list_options() {
+    assert_snapshot!(text_diagnostics(r#"\begin{enumerate}[label=\textbf{\arabic*}, leftmargin=*, wide]\item a\end{enumerate}"#), @r###"
+    warning unsupported 17..62: unsupported list option: label=\textbf{\arabic*}
+    warning unsupported 17..62: unsupported list option: leftmargin=*
+    warning unsupported 17..62: unsupported list option: wide
+    "###);
+    assert_snapshot!(text_diagnostics(r#"\begin{itemize}{\item[x] a}\end{itemize}"#), @"warning unsupported 16..24: ignored label of item not directly in the list");
+}
+
+#[test]
+fn footnote_mark_without_text() {
+    assert_snapshot!(text_diagnostics(r#"a\footnotemark b"#), @"warning unsupported 1..14: footnote mark without footnote text");
+}
+
+#[test]
+fn unknown_color() {
+    assert_snapshot!(text_diagnostics(r#"\textcolor{nope}{a} \textcolor[foo]{1}{b} \textcolor{red!x}{c}"#), @r###"
+    warning unknown-color 10..16: unknown color: nope
+    warning unsupported 35..38: unsupported color model: foo
+    warning unsupported 52..59: invalid color expression: red!x
+    "###);
+}
+
+#[test]
+fn theorem_declaration() {
+    assert_snapshot!(text_diagnostics(r#"\newtheorem{lem}[thm]{Lemma}\newtheorem{cor}{Corollary}[lemma]\theoremstyle{fancy}"#), @r###"
+    warning unsupported 16..21: unknown theorem counter: thm
+    warning unsupported 55..62: unsupported theorem counter: lemma
+    warning unsupported 75..82: unsupported theorem style: fancy
+    "###);
+}
+
+#[test]
+fn layout_settings() {
+    assert_snapshot!(text_diagnostics(r#"\geometry{a4paper, showframe, top=\topskip}\setlength{\tabcolsep}{2pt}\pagenumbering{fnsymbol}\usepackage[klingon]{babel}"#), @r###"
+    warning unsupported 9..43: unsupported geometry option: showframe
+    warning unsupported 9..43: unsupported length: \topskip
+    warning unsupported 43..70: unsupported length: \tabcolsep
+    warning unsupported 84..94: unsupported page numbering: fnsymbol
+    warning unsupported 105..114: unsupported language: klingon
+    "###);
+}
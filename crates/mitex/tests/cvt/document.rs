@@ -0,0 +1,209 @@
+use super::prelude::*;
+use mitex::{Author, ConvertOptions, DocumentMetadata, DocumentTemplate};
+
+fn document(input: &str) -> (String, DocumentMetadata) {
+    let converted = mitex::convert(input, &ConvertOptions::default().document(true));
+    let metadata = converted.metadata.clone();
+    (converted.into_result().unwrap(), metadata)
+}
+
+#[test]
+fn preamble() {
+    let (output, metadata) = document(
+        r#"\documentclass[11pt, twocolumn]{article}
+\usepackage[utf8]{inputenc}
+\newtheorem{thm}{Theorem}
+\iftypst
+#leaked
+\fi
+leaked
+\begin{document}
+\begin{thm}a\end{thm}
+\end{document}
+leaked \unknown"#,
+    );
+    assert_snapshot!(output, @r###"
+    #show: mitex-article.with(font-size: 11pt, columns: 2)
+
+    #mitex-theorem(kind: "thm", supplement: [Theorem])[a];
+    "###);
+    assert_eq!(metadata.class.as_deref(), Some("article"));
+    assert_eq!(metadata.class_options, ["11pt", "twocolumn"]);
+    // the whole input is the body without `document`
+    assert_snapshot!(document(r#"\title{A}a \begin{itemize}\item b\end{itemize}"#).0, @r###"
+    #set document(title: [A])
+    a 
+    - b
+    "###);
+}
+
+#[test]
+fn title_block() {
+    let (output, metadata) = document(
+        r#"\title{On \emph{Primes}}
+\author{Ada Lovelace\thanks{Corresponding.} \\ Engines Ltd. \\ London \and Charles Babbage}
+\affiliation{Cambridge}
+\date{1843}
+\keywords{primes, number theory; proofs}
+\begin{document}
+\maketitle
+\end{document}"#,
+    );
+    assert_snapshot!(output, @r###"
+    #set document(title: [On #emph[Primes];], author: ("Ada Lovelace", "Charles Babbage"), keywords: ("primes", "number theory", "proofs"))
+
+    #mitex-title(title: [On #emph[Primes];], authors: ((name: [Ada Lovelace#footnote[Corresponding.]], affiliation: [Engines Ltd. \ London]), (name: [Charles Babbage], affiliation: [Cambridge]),), date: [1843]);
+    "###);
+    assert_eq!(
+        metadata.authors[0],
+        Author {
+            name: "Ada Lovelace".into(),
+            plain_name: "Ada Lovelace".into(),
+            affiliations: vec!["Engines Ltd.".into(), "London".into()],
+            thanks: vec!["Corresponding.".into()],
+        }
+    );
+    assert_eq!(metadata.authors[1].affiliations, ["Cambridge"]);
+    assert_eq!(metadata.keywords, ["primes", "number theory", "proofs"]);
+}
+
+#[test]
+fn abstract_env() {
+    let (output, metadata) = document(
+        r#"\begin{document}\begin{abstract}
+We prove \textbf{things}.
+\end{abstract}\end{document}"#,
+    );
+    assert_snapshot!(output, @"#mitex-abstract[We prove #strong[things];.];");
+    assert_eq!(
+        metadata.r#abstract.as_deref(),
+        Some("We prove #strong[things];.")
+    );
+}
+
+#[test]
+fn class_template() {
+    let (output, _) = document(
+        r#"\documentclass[a4paper, 11pt, twocolumn, draft]{IEEEtran}
+\title{Typst}
+\author{Martin Haug \\ Typst GmbH}
+\keywords{typesetting}
+\begin{document}
+\maketitle
+\begin{abstract}
+We introduce Typst.
+\end{abstract}
+Text.
+\end{document}"#,
+    );
+    // the title block and the abstract are shown by the template
+    assert_snapshot!(output, @r###"
+    #set document(title: [Typst], author: ("Martin Haug",), keywords: ("typesetting",))
+    #show: mitex-ieee.with(title: [Typst], authors: ((name: [Martin Haug], affiliations: ([Typst GmbH],), thanks: ()),), abstract: [We introduce Typst.], keywords: ("typesetting",), paper: "a4", font-size: 11pt, columns: 2)
+
+
+
+    Text.
+    "###);
+    // classes without templates keep the title block
+    assert_snapshot!(document(r#"\documentclass{memoir}\title{A}\begin{document}\maketitle\end{document}"#).0, @r###"
+    #set document(title: [A])
+    #mitex-title(title: [A]);
+    "###);
+}
+
+#[test]
+fn ieee_author_blocks() {
+    let (output, metadata) = document(
+        r#"\documentclass{IEEEtran}
+\title{Typst}
+\author{\IEEEauthorblockN{Martin Haug}
+\IEEEauthorblockA{Typst GmbH \\ Berlin}
+\and
+\IEEEauthorblockN{Laurenz Mädje\thanks{Corresponding author.}}
+\IEEEauthorblockA{Typst GmbH}}
+\begin{document}
+\maketitle
+\begin{IEEEkeywords}
+typesetting, markup
+\end{IEEEkeywords}
+Text.
+\end{document}"#,
+    );
+    assert_snapshot!(output, @r###"
+    #set document(title: [Typst], author: ("Martin Haug", "Laurenz Mädje"), keywords: ("typesetting", "markup"))
+    #show: mitex-ieee.with(title: [Typst], authors: ((name: [Martin Haug], affiliations: ([Typst GmbH], [Berlin]), thanks: ()), (name: [Laurenz Mädje], affiliations: ([Typst GmbH],), thanks: ([Corresponding author.],))), keywords: ("typesetting", "markup"))
+
+
+
+    Text.
+    "###);
+    assert_eq!(metadata.keywords, ["typesetting", "markup"]);
+}
+
+#[test]
+fn custom_template() {
+    let mut template = DocumentTemplate::new("ieee");
+    template.import = Some("ieee.typ".into());
+    template.args.insert("columns".into(), "1".into());
+    template
+        .args
+        .insert("bibliography-file".into(), r#""refs.bib""#.into());
+    let opts = ConvertOptions::default()
+        .document(true)
+        .template("IEEEtran", template);
+    let converted = mitex::convert(
+        r#"\documentclass[twocolumn]{IEEEtran}\date{2024}\begin{document}a\end{document}"#,
+        &opts,
+    );
+    assert_snapshot!(converted.into_result().unwrap(), @r###"
+    #import "ieee.typ": ieee
+    #show: ieee.with(date: [2024], bibliography-file: "refs.bib", columns: 1)
+    a
+    "###);
+}
+
+#[test]
+fn layout() {
+    let (output, _) = document(
+        r#"\documentclass[a4paper, 12pt]{memoir}
+\usepackage[margin=1in, left=1.5cm]{geometry}
+\usepackage[english, ngerman]{babel}
+\setlength{\parindent}{0pt}
+\setlength{\parskip}{6pt plus 2pt}
+\onehalfspacing
+\pagestyle{plain}
+\pagenumbering{roman}
+\begin{document}
+a
+\end{document}"#,
+    );
+    assert_snapshot!(output, @r###"
+    #set page(margin: (left: 1.5cm, rest: 1in), numbering: "i", paper: "a4")
+    #set par(first-line-indent: 0pt, leading: 0.975em)
+    #show par: set block(spacing: 5.978pt)
+    #set text(lang: "de", size: 12pt)
+
+    a
+    "###);
+    // the options of the class are passed to the template instead
+    let (output, _) = document(
+        r#"\documentclass[twocolumn]{article}
+\usepackage{polyglossia}
+\setmainlanguage[variant=british]{english}
+\geometry{landscape, hmargin=2cm}
+\linespread{2}
+\pagestyle{empty}
+\begin{document}
+a
+\end{document}"#,
+    );
+    assert_snapshot!(output, @r###"
+    #set page(flipped: true, margin: (x: 2cm), numbering: none)
+    #set par(leading: 1.95em)
+    #set text(lang: "en", region: "gb")
+    #show: mitex-article.with(columns: 2)
+
+    a
+    "###);
+}
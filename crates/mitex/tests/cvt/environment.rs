@@ -30,12 +30,30 @@ fn arguments() {
             r#"\begin{array}{lc}
 a & b \\
 c & d
-\end{array}"#).unwrap(), @r###"
-    mitexarray(arg0: l c ,
-    a  zws , b  zws ;
-    c  zws , d 
-    )
-    "###);
+\end{array}"#).unwrap(), @"mat(delim: #none, & a, b; & c, d)");
+}
+
+#[test]
+fn array_augment() {
+    assert_snapshot!(convert_math(
+            r#"\begin{array}{rr|r}
+1 & 2 & 3 \\
+\hline
+4 & & 6 \\
+\end{array}"#).unwrap(), @"mat(delim: #none, augment: #(hline: 1, vline: 2), 1 &, 2 &, 3 &; 4 &, zws &, 6 &)");
+    assert_snapshot!(convert_text(
+            r#"\begin{array}{c|c|c}a & b & c\end{array}"#).unwrap(), @"$ mat(delim: #none, augment: #(vline: (1, 2)), a, b, c) $");
+}
+
+#[test]
+fn matrix_alignment() {
+    assert_snapshot!(convert_math(
+            r#"\begin{pmatrix*}[r]
+-1 & 2 \\
+3 & -4
+\end{pmatrix*}"#).unwrap(), @r###"mat(delim: "(", - 1 &, 2 &; 3 &, - 4 &)"###);
+    assert_snapshot!(convert_math(
+            r#"\begin{bmatrix*}a & b\end{bmatrix*}"#).unwrap(), @r###"mat(delim: "[", a, b)"###);
 }
 
 #[test]
@@ -0,0 +1,74 @@
+use super::prelude::*;
+use mitex::command_preludes::*;
+use mitex::{
+    CmdItem, CommandSpec, ConvertContext, ConvertError, ConvertOptions, EnvItem, Handlers,
+    LaTeXMode,
+};
+
+fn custom_spec() -> CommandSpec {
+    let mut builder = SpecBuilder::default();
+    builder
+        .add_command("todo", define_command(1))
+        .add_command("note", define_normal_env(None, "note"));
+    CommandSpec::overlay(&DEFAULT_SPEC, &[builder.build()])
+}
+
+fn todo(cx: &mut ConvertContext, f: &mut String, cmd: &CmdItem) -> Result<(), ConvertError> {
+    f.push_str("#text(red)[");
+    for arg in cmd.arguments() {
+        cx.convert(f, arg.into())?;
+    }
+    f.push_str("];");
+    Ok(())
+}
+
+fn note(cx: &mut ConvertContext, f: &mut String, env: &EnvItem) -> Result<(), ConvertError> {
+    f.push_str("#block(stroke: 1pt)[");
+    cx.convert_env_body(f, env)?;
+    f.push_str("];");
+    Ok(())
+}
+
+fn convert(input: &str, opts: ConvertOptions) -> Result<String, String> {
+    mitex::convert(input, &opts.spec(Some(custom_spec()))).into_result()
+}
+
+#[test]
+fn test_command_handler() {
+    let opts = ConvertOptions::default().command_handler("todo", todo);
+    assert_snapshot!(convert(r#"a \todo{fix $x$} b"#, opts).unwrap(), @"a #text(red)[fix #math.equation(block: false, $x $);]; b");
+    // a handler overrides the built-in one
+    let opts = ConvertOptions::default().command_handler(
+        "textbf",
+        |cx: &mut ConvertContext, f: &mut String, cmd: &CmdItem| {
+            if matches!(cx.mode(), LaTeXMode::Math) {
+                return cx.convert_command(f, cmd);
+            }
+            f.push_str("#text(weight: \"bold\")[");
+            for arg in cmd.arguments() {
+                cx.convert(f, arg.into())?;
+            }
+            f.push_str("];");
+            Ok(())
+        },
+    );
+    assert_snapshot!(convert(r#"\textbf{a} $\textbf{b}$"#, opts).unwrap(), @r###"#text(weight: "bold")[a]; #math.equation(block: false, $#textbf[b];$);"###);
+}
+
+#[test]
+fn test_env_handler() {
+    let opts = ConvertOptions::default().env_handler("note", note);
+    assert_snapshot!(convert(r#"\begin{note}a \textit{b}\end{note}"#, opts).unwrap(), @"#block(stroke: 1pt)[a #emph[b];];");
+}
+
+#[test]
+fn test_empty_handlers() {
+    // everything is converted according to the spec
+    let opts = ConvertOptions::default().handlers(Handlers::empty());
+    assert_snapshot!(convert(r#"\textbf{a}"#, opts.clone()).unwrap(), @"#textbf[a];");
+    assert_snapshot!(convert(r#"\item a"#, opts).unwrap(), @"#mitexitem; a");
+    let mut handlers = Handlers::default();
+    handlers.remove_command("textit");
+    let opts = ConvertOptions::default().handlers(handlers);
+    assert_snapshot!(convert(r#"\textbf{a}\textit{b}"#, opts).unwrap(), @"#strong[a];#textit[b];");
+}
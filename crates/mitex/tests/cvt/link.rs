@@ -0,0 +1,26 @@
+use super::prelude::*;
+
+#[test]
+fn footnote() {
+    assert_snapshot!(convert_text(r#"a\footnote{note with $x$}. b\footnote[7]{c}"#).unwrap(), @"a#footnote[note with #math.equation(block: false, $x $);];. b#footnote[c];");
+    assert_snapshot!(convert_math(r#"x \footnote{y}"#).unwrap(), @"x  #footnote[y]");
+}
+
+#[test]
+fn footnote_mark() {
+    assert_snapshot!(convert_text(r#"a\footnotemark{} and b\footnotemark. \footnotetext{one}\footnotetext{two}"#).unwrap(), @"a#footnote[one]; and b#footnote[two];.");
+    assert_snapshot!(convert_text(r#"a \footnotetext{alone}"#).unwrap(), @"a #footnote[alone];");
+}
+
+#[test]
+fn href() {
+    assert_snapshot!(convert_text(r#"see \href{https://example.com/a_b?x=1%20&y#top}{the \textbf{site}}"#).unwrap(), @r###"see #link("https://example.com/a_b?x=1%20&y#top")[the #strong[site];];"###);
+    assert_snapshot!(convert_text(r#"\href{https://example.com/\#a\%20}{escaped} \url{https://example.com/a_b#c%20} \nolinkurl{a_b%c}"#).unwrap(), @r###"#link("https://example.com/#a%20")[escaped]; #link("https://example.com/a_b#c%20"); #"a_b%c";"###);
+    assert_snapshot!(convert_math(r#"x = \url{u_1}"#).unwrap(), @r###"x  =  #link("u_1")"###);
+}
+
+#[test]
+fn hyperlink() {
+    assert_snapshot!(convert_text(r#"\hypertarget{sec:intro}{Intro} and \hyperlink{sec:intro}{back to it}"#).unwrap(), @"#[Intro]<sec:intro> and #link(<sec:intro>)[back to it];");
+    assert_snapshot!(convert_text(r#"\hypertarget{a b}{x}.Next \hyperlink{a b}{y}"#).unwrap(), @r###"#[x]#label("a b");.Next #link(label("a b"))[y];"###);
+}
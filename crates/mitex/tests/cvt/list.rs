@@ -0,0 +1,115 @@
+use super::prelude::*;
+
+#[test]
+fn itemize() {
+    assert_snapshot!(convert_text(r#"\begin{itemize}
+\item a
+\item b
+\end{itemize}"#).unwrap(), @r###"
+
+      
+    - a
+      
+    - b
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{enumerate}
+\item a
+\begin{itemize}
+\item b
+\end{itemize}
+\item c
+\end{enumerate}"#).unwrap(), @r###"
+
+      
+    + a
+      
+        
+      - b
+        
+      
+    + c
+    "###);
+}
+
+#[test]
+fn description() {
+    assert_snapshot!(convert_text(r#"\begin{description}
+\item[Term] a
+\item [\textbf{Other}] b
+\item c
+\end{description}"#).unwrap(), @r###"
+
+      
+    / Term: a
+      
+    / #strong[Other];: b
+      
+    / : c
+    "###);
+}
+
+#[test]
+fn custom_label() {
+    assert_snapshot!(convert_text(r#"\begin{itemize}
+\item[--] a
+\item b
+\end{itemize}"#).unwrap(), @r###"
+
+      
+    #list(marker: [–])[a
+      ];
+    - b
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{enumerate}
+\item a
+\item[$\star$] b
+\item c
+\end{enumerate}"#).unwrap(), @r###"
+
+      
+    + a
+      
+    #list(marker: [#math.equation(block: false, $star $);])[b
+      ];
+    2. c
+    "###);
+}
+
+#[test]
+fn enumitem() {
+    assert_snapshot!(convert_text(r#"\begin{enumerate}[label=(\alph*), start=3, noitemsep]
+\item a
+\item b
+\end{enumerate}"#).unwrap(), @r###"
+    #[#set enum(numbering: "(a)")
+      
+    3. a
+      
+    + b
+      ]
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{enumerate}[label={\Roman*.}]
+\item a
+\end{enumerate}"#).unwrap(), @r###"
+    #[#set enum(numbering: "I.")
+      
+    + a
+      ]
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{enumerate}[i)]
+\item a
+\end{enumerate}"#).unwrap(), @r###"
+    #[#set enum(numbering: "i)")
+      
+    + a
+      ]
+    "###);
+    assert_snapshot!(convert_text(r#"\begin{itemize}[label=$\circ$]
+\item a
+\end{itemize}"#).unwrap(), @r###"
+    #[#set list(marker: [#math.equation(block: false, $compose $);])
+      
+    - a
+      ]
+    "###);
+}
@@ -12,7 +12,7 @@ fn test_convert_greek() {
 
 #[test]
 fn test_convert_command() {
-    assert_snapshot!(convert_math(r#"$\int_1^2 x \mathrm{d} x$"#).unwrap(), @"integral _(1 )^(2 ) x  upright(d ) x ");
+    assert_snapshot!(convert_math(r#"$\int_1^2 x \mathrm{d} x$"#).unwrap(), @r###"integral _(1 )^(2 ) x  upright("d") x"###);
     assert_snapshot!(convert_math(r#"$\underline{T}$"#).unwrap(), @"underline(T )");
 }
 
@@ -123,12 +123,12 @@ fn test_convert_lr() {
 
 #[test]
 fn test_convert_color() {
-    assert_snapshot!(convert_math(r#"$x\color{red}yz\frac{1}{2}$"#).unwrap(), @"x mitexcolor(r e d ,y z frac(1 ,2 ))");
-    assert_snapshot!(convert_math(r#"$x\textcolor{red}yz$"#).unwrap(), @"x colortext(r e d ,y )z ");
-    assert_snapshot!(convert_math(r#"$x\textcolor{red}{yz}$"#).unwrap(), @"x colortext(r e d ,y z )");
-    assert_snapshot!(convert_math(r#"$x\colorbox{red}yz$"#).unwrap(), @"x colorbox(r e d ,y )z "
+    assert_snapshot!(convert_math(r#"$x\color{red}yz\frac{1}{2}$"#).unwrap(), @"x text(fill: #rgb(100%, 0%, 0%), y z frac(1 ,2 ))");
+    assert_snapshot!(convert_math(r#"$x\textcolor{red}yz$"#).unwrap(), @"x text(fill: #rgb(100%, 0%, 0%), y )z");
+    assert_snapshot!(convert_math(r#"$x\textcolor{red}{yz}$"#).unwrap(), @"x text(fill: #rgb(100%, 0%, 0%), y z )");
+    assert_snapshot!(convert_math(r#"$x\colorbox{red}yz$"#).unwrap(), @"x #box(fill: rgb(100%, 0%, 0%), inset: 3pt, $y $)z"
     );
-    assert_snapshot!(convert_math(r#"$x\colorbox{red}{yz}$"#).unwrap(), @"x colorbox(r e d ,y z )"
+    assert_snapshot!(convert_math(r#"$x\colorbox{red}{yz}$"#).unwrap(), @"x #box(fill: rgb(100%, 0%, 0%), inset: 3pt, $y z $)"
     );
 }
 
@@ -176,12 +176,7 @@ a & b & c
 a & b & c
 \end{array}$"#
         ).unwrap(),
-        @r###"
-    mitexarray(arg0: l c r ,
-    1  zws , 2  zws , 3 zws ;
-    a  zws , b  zws , c 
-    )
-    "###
+        @"mat(delim: #none, & 1, 2, 3 &; & a, b, c &)"
     );
 }
 
@@ -247,8 +242,7 @@ fn test_convert_text() {
     assert_snapshot!(convert_math(r#"$\text{ab*c}$"#).unwrap(), @r###"#textmath[ab\*c];"###);
     assert_snapshot!(convert_math(r#"$\text{ab_c}$"#).unwrap(), @r###"#textmath[ab\_c];"###);
     assert_snapshot!(convert_math(r#"$\text{ab^c}$"#).unwrap(), @r###"#textmath[ab\^c];"###);
-    // note: hack doesn't work in this case
-    assert_snapshot!(convert_math(r#"$\text{ab\color{red}c}$"#).unwrap(), @"#textmath[abmitexcolor(red,c)];");
+    assert_snapshot!(convert_math(r#"$\text{ab\color{red}c}$"#).unwrap(), @"#textmath[ab#text(fill: rgb(100%, 0%, 0%))[c];];");
 }
 
 #[test]
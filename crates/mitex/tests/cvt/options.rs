@@ -0,0 +1,118 @@
+use super::prelude::*;
+use mitex::{ConvertOptions, LaTeXMode, MathStyle, UnknownCommandPolicy};
+use mitex_parser::syntax::SyntaxKind;
+
+fn text(input: &str, opts: ConvertOptions) -> String {
+    let converted = mitex::convert(input, &opts.mode(LaTeXMode::Text));
+    converted.into_result().unwrap()
+}
+
+fn math(input: &str, opts: ConvertOptions) -> String {
+    let converted = mitex::convert(input, &opts.mode(LaTeXMode::Math));
+    converted.into_result().unwrap()
+}
+
+#[test]
+fn test_math_style() {
+    let opts = ConvertOptions::default();
+    assert_snapshot!(math(r#"\frac{a}{b}"#, opts.clone()), @"frac(a ,b )");
+    assert_snapshot!(math(r#"\frac{a}{b}"#, opts.clone().math_style(MathStyle::Inline)), @"$frac(a ,b )$");
+    assert_snapshot!(math(r#"\frac{a}{b}"#, opts.clone().math_style(MathStyle::Display)), @"$ frac(a ,b ) $");
+    // takes no effect in text mode
+    assert_snapshot!(text(r#"$a$"#, opts.math_style(MathStyle::Display)), @"#math.equation(block: false, $a $);");
+}
+
+#[test]
+fn test_emit_labels() {
+    let input = r#"\section{Intro}\label{sec:intro}
+\begin{equation}
+a\label{eq:a}
+\end{equation}"#;
+    let opts = ConvertOptions::default();
+    assert_snapshot!(text(input, opts.clone()), @r###"
+    #set heading(numbering: "1.1");#heading(level: 1)[Intro];<sec:intro>
+    $ aligned(
+    a 
+    ) $<eq:a>
+    "###);
+    assert_snapshot!(text(input, opts.emit_labels(false)), @r###"
+    #set heading(numbering: "1.1");#heading(level: 1)[Intro];
+    $ aligned(
+    a 
+    ) $
+    "###);
+}
+
+#[test]
+fn test_emit_prelude() {
+    let opts = ConvertOptions::default().emit_prelude(true);
+    let output = text(r#"$\alpha$"#, opts);
+    assert!(output.starts_with("\n#import \"@preview/mitex:"));
+    assert!(output.contains(r#"#let RR = mitex-scope.at("RR", default: none);"#));
+    assert!(!output.contains("mitex-error"));
+    assert!(output.ends_with("\n\n#math.equation(block: false, $alpha $);"));
+    // the placeholder is defined only if used
+    let opts = ConvertOptions::default().emit_prelude(true).recover(true);
+    let converted = mitex::convert(r#"\unknown"#, &opts);
+    assert!(converted.output.contains(r#"#let mitex-error = "#));
+    assert!(converted
+        .output
+        .ends_with("\n\n#mitex-error(\"unknown command: \\\\unknown\");"));
+}
+
+#[test]
+fn test_unknown_command() {
+    let opts = ConvertOptions::new(LaTeXMode::Math);
+    let converted = mitex::convert(r#"a \foo b"#, &opts);
+    assert_snapshot!(converted.into_result().unwrap_err(), @r###"error: unknown command: \foo"###);
+
+    let keep = opts.clone().unknown_command(UnknownCommandPolicy::Keep);
+    let converted = mitex::convert(r#"a \foo b"#, &keep);
+    assert!(!converted.has_errors());
+    assert_snapshot!(converted.output, @"a  foo  b");
+    assert_snapshot!(format!("{:?}", converted.diagnostics), @r###"[Diagnostic { severity: Warning, code: UnknownCommand, message: "unknown command: \\foo", range: 2..6 }]"###);
+    assert_snapshot!(text(r#"a \foo{b} c"#, keep), @"a foob c");
+
+    let ignore = opts.unknown_command(UnknownCommandPolicy::Ignore);
+    assert_snapshot!(math(r#"a \foo b"#, ignore.clone()), @"a   b");
+    assert_snapshot!(text(r#"a \foo{b} c"#, ignore), @"a b c");
+}
+
+#[test]
+fn test_expand_macros() {
+    let input = r#"\newcommand{\f}{x}\f"#;
+    let opts = ConvertOptions::new(LaTeXMode::Math);
+    assert_snapshot!(math(input, opts.clone()), @"x");
+    let converted = mitex::convert(input, &opts.expand_macros(false));
+    assert_snapshot!(converted.into_result().unwrap_err(), @r###"error: unknown command: \f"###);
+}
+
+#[test]
+fn test_convert_node() {
+    let input = r#"Let $\frac{a}{b}$ be \textbf{c}."#;
+    let root = mitex_parser::parse(input, DEFAULT_SPEC.clone());
+    let formula = root
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::ItemFormula)
+        .unwrap();
+    let opts = ConvertOptions::default();
+    let converted = mitex::convert_node(&formula, &opts);
+    assert_snapshot!(converted.output, @"#math.equation(block: false, $frac(a ,b )$);");
+
+    // convert a part of the formula in math mode
+    let frac = formula
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::ItemCmd)
+        .unwrap();
+    let converted = mitex::convert_node(&frac, &opts.clone().mode(LaTeXMode::Math));
+    assert_snapshot!(converted.output, @"frac(a ,b )");
+
+    // ranges are relative to the whole tree
+    let root = mitex_parser::parse(r#"Let $\foo$"#, DEFAULT_SPEC.clone());
+    let formula = root
+        .descendants()
+        .find(|node| node.kind() == SyntaxKind::ItemFormula)
+        .unwrap();
+    let converted = mitex::convert_node(&formula, &opts);
+    assert_snapshot!(format!("{:?}", converted.diagnostics), @r###"[Diagnostic { severity: Error, code: UnknownCommand, message: "unknown command: \\foo", range: 5..9 }]"###);
+}
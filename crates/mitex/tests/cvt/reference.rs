@@ -0,0 +1,27 @@
+use super::prelude::*;
+
+#[test]
+fn test_reference() {
+    assert_snapshot!(convert_text(r#"Figure~\ref{fig:a} and \autoref{fig:b}"#).unwrap(), @"Figure~#ref(<fig:a>, supplement: none); and #ref(<fig:b>);");
+    assert_snapshot!(convert_text(r#"see \eqref{eq:1} on page \pageref{eq:1}"#).unwrap(), @"see (#ref(<eq:1>, supplement: none)) on page #context counter(page).at(<eq:1>).first();");
+}
+
+#[test]
+fn test_reference_list() {
+    assert_snapshot!(convert_text(r#"\cref{fig:a}"#).unwrap(), @"#ref(<fig:a>);");
+    assert_snapshot!(convert_text(r#"\cref{fig:a,fig:b}"#).unwrap(), @"#ref(<fig:a>) and #ref(<fig:b>);");
+    assert_snapshot!(convert_text(r#"\Cref{fig:a, fig:b, tab:c}"#).unwrap(), @"#ref(<fig:a>), #ref(<fig:b>) and #ref(<tab:c>);");
+}
+
+#[test]
+fn test_reference_math() {
+    assert_snapshot!(convert_math(r#"x \text{by } \eqref{eq:1}"#).unwrap(), @"x  #textmath[by ]; (#ref(<eq:1>, supplement: none))");
+}
+
+#[test]
+fn test_label_normalization() {
+    // names are normalized consistently at both sites
+    assert_snapshot!(convert_text(r#"\section{A}\label{sec:my label} see \ref{sec:my label}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[A];<sec:my-label> see #ref(<sec:my-label>, supplement: none);"###);
+    assert_snapshot!(convert_text(r#"\begin{equation}a\label{eq/1+2}\end{equation} \eqref{eq/1+2}"#).unwrap(), @"$ aligned(a ) $<eq-1-2> (#ref(<eq-1-2>, supplement: none))");
+    assert_snapshot!(convert_text(r#"\label{fig:} \ref{fig:}"#).unwrap(), @"<fig_> #ref(<fig_>, supplement: none);");
+}
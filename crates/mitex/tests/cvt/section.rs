@@ -0,0 +1,63 @@
+use super::prelude::*;
+
+#[test]
+fn test_heading_levels() {
+    assert_snapshot!(convert_text(r#"\section{A}\subsection{B}\subsubsection{C}\paragraph{D}\subparagraph{E}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[A];#heading(level: 2)[B];#heading(level: 3)[C];#heading(level: 4)[D];#heading(level: 5)[E];"###);
+    // levels are shifted if the document has chapters or parts
+    assert_snapshot!(convert_text(r#"\chapter{A}\section{B}\subsection{C}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[A];#heading(level: 2)[B];#heading(level: 3)[C];"###);
+    assert_snapshot!(convert_text(r#"\part{A}\chapter{B}\section{C}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[A];#heading(level: 2)[B];#heading(level: 3)[C];"###);
+}
+
+#[test]
+fn test_heading_starred() {
+    assert_snapshot!(convert_text(r#"\section*{Acknowledgements}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1, numbering: none, outlined: false)[Acknowledgements];"###);
+    assert_snapshot!(convert_text(r#"\chapter*{Preface} \section{A}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1, numbering: none, outlined: false)[Preface]; #heading(level: 2)[A];"###);
+}
+
+#[test]
+fn test_heading_short_title() {
+    assert_snapshot!(convert_text(r#"\section[Intro]{Introduction to $x$}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[#mitex-short-title([Intro], [Introduction to #math.equation(block: false, $x $);])];"###);
+    let converted = mitex::convert(
+        r#"\section[Intro]{Introduction}"#,
+        &mitex::ConvertOptions::default().emit_prelude(true),
+    );
+    assert!(converted.output.contains(
+        r#"#let mitex-short-title = mitex-scope.at("mitex-short-title", default: none);"#
+    ));
+}
+
+#[test]
+fn test_heading_label() {
+    assert_snapshot!(convert_text(r#"\section{Intro}\label{sec:intro}"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[Intro];<sec:intro>"###);
+    assert_snapshot!(convert_text(r#"\section{Intro} \label{sec:intro} text"#).unwrap(), @r###"#set heading(numbering: "1.1");#heading(level: 1)[Intro];<sec:intro>  text"###);
+    assert_snapshot!(convert_text(r#"\section{Intro}
+\label{sec:intro}
+text \label{other}"#).unwrap(), @r###"
+    #set heading(numbering: "1.1");#heading(level: 1)[Intro];<sec:intro>
+
+    text <other>
+    "###);
+}
+
+#[test]
+fn test_appendix() {
+    assert_snapshot!(convert_text(r#"\section{A}
+\appendix
+\section{Proofs}"#).unwrap(), @r###"
+    #set heading(numbering: "1.1");#heading(level: 1)[A];
+    #counter(heading).update(0);
+    #set heading(numbering: "A.1");
+    #heading(level: 1)[Proofs];
+    "###);
+}
+
+#[test]
+fn test_outline() {
+    assert_snapshot!(convert_text(r#"\tableofcontents
+\listoffigures
+\listoftables"#).unwrap(), @r###"
+    #outline(indent: auto);
+    #outline(title: [List of Figures], target: figure.where(kind: image));
+    #outline(title: [List of Tables], target: figure.where(kind: table));
+    "###);
+}
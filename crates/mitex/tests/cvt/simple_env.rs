@@ -8,6 +8,6 @@ fn quote() {
 
 #[test]
 fn test_abstract() {
-    assert_snapshot!(convert_text(r#"\begin{abstract}\end{abstract}"#).unwrap(), @"#quote(block: true)[];");
-    assert_snapshot!(convert_text(r#"\begin{abstract}yes\end{abstract}"#).unwrap(), @"#quote(block: true)[yes];");
+    assert_snapshot!(convert_text(r#"\begin{abstract}\end{abstract}"#).unwrap(), @"#mitex-abstract[];");
+    assert_snapshot!(convert_text(r#"\begin{abstract}yes\end{abstract}"#).unwrap(), @"#mitex-abstract[yes];");
 }
@@ -0,0 +1,70 @@
+use super::prelude::*;
+
+fn source_map(input: &str, math: bool) -> String {
+    let spec = Some(DEFAULT_SPEC.clone());
+    let converted = if math {
+        mitex::convert_math_with_source_map(input, spec, false)
+    } else {
+        mitex::convert_text_with_source_map(input, spec, false)
+    };
+    let output = converted.output;
+    converted
+        .source_map
+        .unwrap()
+        .into_iter()
+        .map(|m| {
+            format!(
+                "{:?} {:?} <- {:?} {:?}",
+                m.output,
+                &output[m.output.clone()],
+                m.input,
+                &input[m.input.clone()]
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_sorted_mappings() {
+    assert_snapshot!(source_map(r#"\frac{a}{b}"#, true), @r###"
+    0..11 "frac(a ,b )" <- 0..11 "\\frac{a}{b}"
+    5..7 "a " <- 5..8 "{a}"
+    5..7 "a " <- 6..7 "a"
+    8..10 "b " <- 8..11 "{b}"
+    8..10 "b " <- 9..10 "b"
+    "###);
+    assert_snapshot!(source_map(r#"a \textbf{b} $c$"#, false), @r###"
+    0..49 "a #strong[b]; #math.equation(block: false, $c $);" <- 0..16 "a \\textbf{b} $c$"
+    0..2 "a " <- 0..2 "a "
+    0..1 "a" <- 0..1 "a"
+    1..2 " " <- 1..2 " "
+    2..13 "#strong[b];" <- 2..12 "\\textbf{b}"
+    10..11 "b" <- 9..12 "{b}"
+    10..11 "b" <- 10..11 "b"
+    13..14 " " <- 12..13 " "
+    14..49 "#math.equation(block: false, $c $);" <- 13..16 "$c$"
+    44..46 "c " <- 14..15 "c"
+    "###);
+}
+
+#[test]
+fn test_after_environment() {
+    // `\begin{..}` and `\end{..}` are not all in the syntax tree
+    let input = "\\begin{itemize}\n\\item a\n\\end{itemize}\nx $y$\n";
+    let mappings = source_map(input, false);
+    // the root covers the whole input, and `y` is at its position
+    let lines = mappings.lines().enumerate();
+    let lines = lines.filter(|(i, line)| *i == 0 || line.contains("\"y\""));
+    let lines = lines.map(|(_, line)| line).collect::<Vec<_>>();
+    assert_snapshot!(lines.join("\n"), @r###"
+    0..49 "\n  \n- a\n  \nx #math.equation(block: false, $y $);\n" <- 0..44 "\\begin{itemize}\n\\item a\n\\end{itemize}\nx $y$\n"
+    43..45 "y " <- 41..42 "y"
+    "###);
+}
+
+#[test]
+fn test_no_source_map() {
+    let converted = mitex::convert_math_with_diagnostics(r#"a"#, None);
+    assert!(converted.source_map.is_none());
+}
@@ -0,0 +1,30 @@
+use super::prelude::*;
+use mitex::command_preludes::*;
+use mitex::CommandSpec;
+
+fn custom_spec() -> CommandSpec {
+    let mut builder = SpecBuilder::default();
+    builder
+        .add_command("myop", define_command_with_alias(1, "myop"))
+        .add_command("frac", define_command_with_alias(2, "myfrac"));
+    builder.build()
+}
+
+#[test]
+fn test_custom_spec() {
+    // the caller's spec drives both parsing and converting
+    assert_snapshot!(mitex_convert_math(r#"\myop{x}"#, Some(custom_spec())).unwrap(), @"myop(x )");
+    assert_snapshot!(mitex_convert_math(r#"\alpha"#, Some(custom_spec())).unwrap_err(), @r###"error: unknown command: \alpha"###);
+}
+
+#[test]
+fn test_overlay_spec() {
+    let spec = CommandSpec::overlay(&DEFAULT_SPEC, &[custom_spec()]);
+    assert_snapshot!(mitex_convert_math(r#"\myop{x} + \alpha"#, Some(spec.clone())).unwrap(), @"myop(x ) +  alpha");
+    // later layers override earlier ones
+    assert_snapshot!(mitex_convert_math(r#"\frac{a}{b}"#, Some(spec)).unwrap(), @"myfrac(a ,b )");
+    let mut builder = SpecBuilder::default();
+    builder.add_command("myop", define_command_with_alias(1, "myop2"));
+    let spec = CommandSpec::overlay(&DEFAULT_SPEC, &[custom_spec(), builder.build()]);
+    assert_snapshot!(mitex_convert_math(r#"\myop{x} \frac{a}{b}"#, Some(spec)).unwrap(), @"myop2(x ) myfrac(a ,b )");
+}
@@ -23,3 +23,136 @@ fn tabular() {
     );
     "###);
 }
+
+#[test]
+fn tabular_column_spec() {
+    assert_snapshot!(convert_text(r###"\begin{tabular}{||l|p{3cm}m{.5\textwidth}b{1in}|}
+        a & b & c & d \\
+    \end{tabular}"###).unwrap(), @r###"
+    #table(stroke: none,
+    columns: (auto, 3cm, 50%, 1in, ),
+    align: (left, left, left + horizon, left + bottom, ),
+    table.vline(stroke: 1pt, x: 0), table.vline(stroke: .5pt, x: 1), table.vline(stroke: .5pt, x: 4), 
+    [a ], [b ], [c ], [d ],
+    );
+    "###);
+    assert_snapshot!(convert_text(r###"\begin{tabular}{@{}>{\bfseries}l*{2}{c}@{}}
+        a & b & c
+    \end{tabular}"###).unwrap(), @r###"
+    #table(stroke: none,
+    columns: 3,
+    align: (left, center, center, ),
+
+    [#strong[a ]], [b ], [c],
+    );
+    "###);
+}
+
+#[test]
+fn tabular_spans() {
+    assert_snapshot!(convert_text(r###"\begin{tabular}{lll}
+        \toprule
+        \multicolumn{2}{c}{Head} & X \\
+        \cmidrule(lr){1-2}
+        \multirow{2}{*}{A} & b & \\
+        & d & \multirow{-2}{*}{C} \\
+        \cline{2-3}
+        \multicolumn{2}{|r|}{\multirow{2}{*}{E}} & f \\
+        \multicolumn{2}{c}{} & g \\
+        \bottomrule
+    \end{tabular}"###).unwrap(), @r###"
+    #table(stroke: none,
+    columns: 3,
+    align: (left, left, left, ),
+
+    table.hline(stroke: 1pt),
+    table.cell(colspan: 2, align: center)[Head], [X ],
+    table.hline(start: 0, end: 2, stroke: .5pt),
+    table.cell(rowspan: 2)[A], [b ], table.cell(rowspan: 2)[C],
+    [d ],
+    table.hline(start: 1, end: 3, stroke: .5pt),
+    table.cell(colspan: 2, rowspan: 2, align: right)[E], [f ],
+    [g ],
+    table.hline(stroke: 1pt),
+    );
+    "###);
+}
+
+#[test]
+fn tabular_width() {
+    assert_snapshot!(convert_text(r###"\begin{tabularx}{\textwidth}{l>{\centering\arraybackslash}XX}
+        a & b & c \\
+    \end{tabularx}"###).unwrap(), @r###"
+    #block(width: 100%)[#table(stroke: none,
+    columns: (auto, 1fr, 1fr, ),
+    align: (left, center, left, ),
+
+    [a ], [b ], [c ],
+    );];
+    "###);
+    assert_snapshot!(convert_text(r###"\begin{tabular*}{.8\linewidth}{@{\extracolsep{\fill}}lr}
+        a & b
+    \end{tabular*}"###).unwrap(), @r###"
+    #block(width: 80%)[#table(stroke: none,
+    columns: 2,
+    column-gutter: 1fr,
+    align: (left, right, ),
+
+    [a ], [b],
+    );];
+    "###);
+}
+
+#[test]
+fn longtable() {
+    assert_snapshot!(convert_text(r###"\begin{longtable}[c]{|l|r|}
+        \caption{Long table} \label{tab:long} \\
+        \hline
+        Name & Value \\
+        \hline
+        \endhead
+        \hline
+        \endfoot
+        a & 1 \\
+        b & 2 \\
+    \end{longtable}"###).unwrap(), @r###"
+    #figure(kind: table, caption: [Long table])[#table(stroke: none,
+    columns: 2,
+    align: (left, right, ),
+    table.vline(stroke: .5pt, x: 0), table.vline(stroke: .5pt, x: 1), table.vline(stroke: .5pt, x: 2), 
+    table.header(repeat: true,
+    table.hline(stroke: .5pt),
+    [Name ], [Value ],
+    table.hline(stroke: .5pt),
+    ),
+    [a ], [1 ],
+    [b ], [2 ],
+    table.footer(repeat: true,
+    table.hline(stroke: .5pt),
+    ),
+    );];<tab:long>
+    "###);
+    assert_snapshot!(convert_text(r###"\begin{longtable}{ll}
+        Name & Value \\
+        \endfirsthead
+        Name (continued) & Value \\
+        \endhead
+        a & 1
+    \end{longtable}"###).unwrap(), @r###"
+    #table(stroke: none,
+    columns: 2,
+    align: (left, left, ),
+
+    table.header(repeat: true,
+    [Name ], [Value ],
+    ),
+    [a ], [1],
+    );
+    "###);
+}
+
+#[test]
+fn tabular_missing_arguments() {
+    assert_snapshot!(convert_text(r###"\begin{longtable}[c]"###).unwrap_err(), @"error: missing column specification of longtable");
+    assert_snapshot!(convert_text(r###"\begin{tabularx}{l}"###).unwrap_err(), @"error: missing width or column specification of tabularx");
+}
@@ -0,0 +1,48 @@
+use super::prelude::*;
+
+#[test]
+fn declaration() {
+    assert_snapshot!(convert_text(r#"\newtheorem{thm}{Theorem}
+\begin{thm}
+There are infinitely many primes.
+\end{thm}"#).unwrap(), @r###"
+
+    #mitex-theorem(kind: "thm", supplement: [Theorem])[There are infinitely many primes.];
+    "###);
+    assert_snapshot!(convert_text(r#"\newtheorem{thm}{Theorem}[section]\newtheorem{lem}[thm]{Lemma}\newtheorem*{rem}{Remark}
+\section{Intro}
+\begin{lem}a\end{lem}\begin{rem}b\end{rem}"#).unwrap(), @r###"
+
+    #set heading(numbering: "1.1");#heading(level: 1)[Intro];
+    #mitex-theorem(kind: "thm", supplement: [Lemma], within: 1)[a];#mitex-theorem(kind: "rem", supplement: [Remark], numbered: false)[b];
+    "###);
+}
+
+#[test]
+fn title_and_label() {
+    assert_snapshot!(convert_text(r#"\newtheorem{thm}{Theorem}\begin{thm}[Euclid [300 BC]]\label{thm:primes}
+There are infinitely many primes $p$.
+\end{thm} See \cref{thm:primes}."#).unwrap(), @r###"#mitex-theorem(kind: "thm", supplement: [Theorem], title: [Euclid \[300 BC\]], label: <thm:primes>)[There are infinitely many primes #math.equation(block: false, $p $);.]; See #ref(<thm:primes>);."###);
+}
+
+#[test]
+fn style() {
+    assert_snapshot!(convert_text(r#"\theoremstyle{definition}\newtheorem{defn}{Definition}\theoremstyle{remark}\newtheorem{note}{Note}
+\begin{defn}a\end{defn}\begin{note}b\end{note}"#).unwrap(), @r###"
+
+    #mitex-theorem(kind: "defn", supplement: [Definition], style: "definition")[a];#mitex-theorem(kind: "note", supplement: [Note], style: "remark")[b];
+    "###);
+}
+
+#[test]
+fn proof() {
+    assert_snapshot!(convert_text(r#"\begin{proof}
+Trivial.
+\end{proof}
+\begin{proof}[Proof of the claim]
+$$x = y \qedhere$$
+\end{proof}"#).unwrap(), @r###"
+    #mitex-proof[Trivial.];
+    #mitex-proof(title: [Proof of the claim])[$ x  =  y    $];
+    "###);
+}
@@ -15,5 +15,5 @@ fn greedy_trivia() {
     assert_snapshot!(convert_math(r#"a {\displaystyle text } b"#).unwrap(), @"a  mitexdisplay( t e x t  ) b ");
     assert_snapshot!(convert_math(r#"\displaystyle text "#).unwrap(), @"mitexdisplay( t e x t  )");
     assert_snapshot!(convert_math(r#"\displaystyle {text} "#).unwrap(), @"mitexdisplay( t e x t , )");
-    assert_snapshot!(convert_math(r#"\displaystyle {\mathrm {text}} "#).unwrap(), @"mitexdisplay( upright(t e x t ), )");
+    assert_snapshot!(convert_math(r#"\displaystyle {\mathrm {text}} "#).unwrap(), @r###"mitexdisplay( upright("text"), )"###);
 }
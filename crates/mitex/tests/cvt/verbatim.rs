@@ -0,0 +1,22 @@
+use super::prelude::*;
+
+#[test]
+fn verb() {
+    assert_snapshot!(convert_text(r#"use \verb|a_b{}| or \verb+\foo%+ and \verb*|a b|"#).unwrap(), @r###"use #raw("a_b{}"); or #raw("\\foo%"); and #raw("a␣b");"###);
+    assert_snapshot!(convert_math(r#"x = \verb|$y$|"#).unwrap(), @r###"x  =  #raw("$y$")"###);
+    assert_snapshot!(convert_text(r#"\lstinline[language=C]{int x = 1;} and \mintinline{Rust}|let x|"#).unwrap(), @r###"#raw(lang: "c", "int x = 1;"); and #raw(lang: "rust", "let x");"###);
+}
+
+#[test]
+fn verbatim() {
+    assert_snapshot!(convert_text(r#"\begin{verbatim}
+    \section{A} $x_1$ % not a comment
+    \end{verbatim}"#).unwrap(), @r###"#raw(block: true, "    \\section{A} $x_1$ % not a comment");"###);
+    assert_snapshot!(convert_text(r#"\begin{lstlisting}[language={[ANSI]C}, numbers=left]
+var s = "}";
+\end{lstlisting}"#).unwrap(), @r###"#raw(block: true, lang: "c", "var s = \"}\";");"###);
+    assert_snapshot!(convert_text(r#"\begin{minted}[linenos]{Python}
+def f():
+    return {"\\": 1}
+\end{minted}"#).unwrap(), @r###"#raw(block: true, lang: "python", "def f():\n    return {\"\\\\\": 1}");"###);
+}
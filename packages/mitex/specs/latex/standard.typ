@@ -62,25 +62,129 @@
   scope,
 ) = process-spec((
   // Text mode
-  section: define-cmd(1, alias: "#heading(level: 1)"),
-  subsection: define-cmd(1, alias: "#heading(level: 2)"),
-  subsubsection: define-cmd(1, alias: "#heading(level: 3)"),
-  footnote: define-cmd(1, alias: "#footnote"),
-  cite: define-cmd(1, alias: "#mitexcite", handle: it => cite(label(get-tex-str(it)))),
+  part: define-glob-cmd("{,b}t", "#heading(level: 1)"),
+  "part*": define-cmd(1, alias: "#heading(level: 1)"),
+  chapter: define-glob-cmd("{,b}t", "#heading(level: 1)"),
+  "chapter*": define-cmd(1, alias: "#heading(level: 1)"),
+  section: define-glob-cmd("{,b}t", "#heading(level: 1)"),
+  "section*": define-cmd(1, alias: "#heading(level: 1)"),
+  subsection: define-glob-cmd("{,b}t", "#heading(level: 2)"),
+  "subsection*": define-cmd(1, alias: "#heading(level: 2)"),
+  subsubsection: define-glob-cmd("{,b}t", "#heading(level: 3)"),
+  "subsubsection*": define-cmd(1, alias: "#heading(level: 3)"),
+  paragraph: define-glob-cmd("{,b}t", "#heading(level: 4)"),
+  "paragraph*": define-cmd(1, alias: "#heading(level: 4)"),
+  subparagraph: define-glob-cmd("{,b}t", "#heading(level: 5)"),
+  "subparagraph*": define-cmd(1, alias: "#heading(level: 5)"),
+  appendix: ignore-sym,
+  tableofcontents: ignore-sym,
+  listoffigures: ignore-sym,
+  listoftables: ignore-sym,
+  footnote: define-glob-cmd("{,b}t", "#footnote", handle: (..args) => footnote(args.pos().last())),
+  footnotemark: define-glob-cmd("{,b}", "#mitexfootnotemark", handle: ignore-me),
+  footnotetext: define-glob-cmd("{,b}t", "#footnote"),
+  // links of hyperref, whose urls are read raw
+  href: define-glob-cmd("vt", "#mitexhref", handle: (url, body) => link(url.text.slice(1, -1), body)),
+  url: define-glob-cmd("v", "#mitexurl", handle: url => link(url.text.slice(1, -1))),
+  nolinkurl: define-glob-cmd("v", "#mitexnolinkurl", handle: url => url.text.slice(1, -1)),
+  hyperlink: define-cmd(2, alias: "#mitexhyperlink", handle: (name, body) => body),
+  hypertarget: define-cmd(2, alias: "#mitexhypertarget", handle: (name, body) => body),
+  cite: define-glob-cmd("{,b}{,b}t", "#mitexcite", handle: it => cite(label(get-tex-str(it)))),
+  citep: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  "citep*": define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Citep: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citealp: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citet: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  "citet*": define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Citet: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citealt: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citeauthor: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  "citeauthor*": define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Citeauthor: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citeyear: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  citeyearpar: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  parencite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Parencite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  autocite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Autocite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  textcite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  Textcite: define-glob-cmd("{,b}{,b}t", "#mitexcite"),
+  nocite: define-cmd(1, alias: "#mitexcite"),
+  bibliography: define-cmd(1, alias: "#mitexbibliography", handle: ignore-me),
+  bibliographystyle: define-cmd(1, alias: "mitexbibliographystyle", handle: ignore-me),
+  addbibresource: define-glob-cmd("{,b}t", "mitexaddbibresource", handle: ignore-me),
+  printbibliography: define-glob-cmd("{,b}", "#mitexbibliography"),
+  bibitem: define-glob-cmd("{,b}t", "mitexbibitem", handle: ignore-me),
+  thebibliography: define-env(1, alias: "mitexthebibliography", handle: ignore-me),
   emph: define-cmd(1, alias: "#emph"),
   label: define-cmd(1, alias: "mitexlabel", handle: ignore-me),
   tag: define-cmd(1, alias: "mitexlabel", handle: ignore-me),
+  "tag*": define-cmd(1, alias: "mitexlabel", handle: ignore-me),
   ref: define-cmd(1, alias: "#mitexref", handle: it => ref(label(get-tex-str(it)))),
   eqref: define-cmd(1, alias: "#mitexref"),
-  item: ignore-sym,
+  autoref: define-cmd(1, alias: "#mitexref"),
+  cref: define-cmd(1, alias: "#mitexref"),
+  Cref: define-cmd(1, alias: "#mitexref"),
+  pageref: define-cmd(1, alias: "#mitexref"),
+  item: define-glob-cmd("{,b}", "#mitexitem", handle: ignore-me),
+  // the title block, whose fields are collected for `\maketitle`
+  title: define-glob-cmd("{,b}t", "mitextitle", handle: ignore-me),
+  author: define-glob-cmd("{,b}t", "mitexauthor", handle: ignore-me),
+  date: define-cmd(1, alias: "mitexdate", handle: ignore-me),
+  affiliation: define-glob-cmd("{,b}t", "mitexaffiliation", handle: ignore-me),
+  keywords: define-cmd(1, alias: "mitexkeywords", handle: ignore-me),
+  IEEEauthorblockN: define-cmd(1, alias: "mitexauthorblock", handle: ignore-me),
+  IEEEauthorblockA: define-cmd(1, alias: "mitexauthorblock", handle: ignore-me),
+  thanks: define-cmd(1, alias: "#footnote"),
+  "and": ignore-sym,
+  maketitle: ignore-sym,
+  today: ignore-sym,
+  // the layout of the document, which is set at the top in the document mode
+  geometry: define-cmd(1, alias: "mitexgeometry", handle: ignore-me),
+  setlength: define-cmd(2, alias: "mitexsetlength", handle: ignore-me),
+  linespread: define-cmd(1, alias: "mitexlinespread", handle: ignore-me),
+  setstretch: define-cmd(1, alias: "mitexsetstretch", handle: ignore-me),
+  singlespacing: ignore-sym,
+  onehalfspacing: ignore-sym,
+  doublespacing: ignore-sym,
+  pagestyle: define-cmd(1, alias: "mitexpagestyle", handle: ignore-me),
+  thispagestyle: define-cmd(1, alias: "mitexthispagestyle", handle: ignore-me),
+  pagenumbering: define-cmd(1, alias: "mitexpagenumbering", handle: ignore-me),
+  setmainlanguage: define-glob-cmd("{,b}t", "mitexsetmainlanguage", handle: ignore-me),
+  setdefaultlanguage: define-glob-cmd("{,b}t", "mitexsetmainlanguage", handle: ignore-me),
+  setotherlanguage: define-glob-cmd("{,b}t", "mitexsetotherlanguage", handle: ignore-me),
+  setotherlanguages: define-cmd(1, alias: "mitexsetotherlanguage", handle: ignore-me),
+  selectlanguage: define-cmd(1, alias: "mitexselectlanguage", handle: ignore-me),
+  // theorems of amsthm, whose environments are declared by `\newtheorem`
+  newtheorem: define-glob-cmd("t{,b}t{,b}", "mitexnewtheorem", handle: ignore-me),
+  "newtheorem*": define-cmd(2, alias: "mitexnewtheorem", handle: ignore-me),
+  theoremstyle: define-cmd(1, alias: "mitextheoremstyle", handle: ignore-me),
+  qedhere: ignore-sym,
   // environments for text mode
-  itemize: define-env(none, kind: "is-itemize"),
-  enumerate: define-env(none, kind: "is-enumerate"),
+  itemize: define-glob-env("{,b}", kind: "is-itemize", alias: "list"),
+  enumerate: define-glob-env("{,b}", kind: "is-enumerate", alias: "enum"),
+  description: define-glob-env("{,b}", kind: "is-description", alias: "terms"),
+  proof: define-glob-env("{,b}", alias: "mitexproof", handle: (..args) => args.pos().last()),
   quote: define-env(none, alias: "quote(block: true)"),
   abstract: define-env(none, alias: "quote(block: true)"),
+  IEEEkeywords: define-env(none, alias: "mitexkeywords", handle: ignore-me),
+  document: define-env(none, alias: "mitexdocument", handle: it => it),
   figure: define-glob-env("{,b}", kind: "is-figure", alias: "figure"),
   table: define-glob-env("{,b}", kind: "is-figure", alias: "figure"),
   tabular: define-env(1, kind: "is-table", alias: "table"),
+  "tabular*": define-env(2, kind: "is-table", alias: "table"),
+  tabularx: define-env(2, kind: "is-table", alias: "table"),
+  longtable: define-glob-env("{,b}t", kind: "is-table", alias: "table"),
+  verbatim: define-env(none, kind: "is-verbatim", alias: "raw"),
+  "verbatim*": define-env(none, kind: "is-verbatim", alias: "raw"),
+  Verbatim: define-glob-env("{,b}", kind: "is-verbatim", alias: "raw"),
+  lstlisting: define-glob-env("{,b}", kind: "is-verbatim", alias: "raw"),
+  minted: define-glob-env("{,b}t", kind: "is-verbatim", alias: "raw"),
+  // commands for verbatim
+  verb: define-glob-cmd("v", "#raw"),
+  "verb*": define-glob-cmd("v", "#raw"),
+  lstinline: define-glob-cmd("{,b}v", "#raw"),
+  mintinline: define-glob-cmd("{,b}tv", "#raw"),
   // commands for figure
   centering: ignore-sym,
   textwidth: sym,
@@ -90,8 +194,16 @@
   toprule: define-glob-cmd("{,b}", "toprule"),
   midrule: define-glob-cmd("{,b}", "midrule"),
   bottomrule: define-glob-cmd("{,b}", "bottomrule"),
+  cline: define-cmd(1, alias: "mitexcline", handle: ignore-me),
+  cmidrule: define-glob-cmd("{,b}{,p}t", "mitexcline", handle: ignore-me),
+  multicolumn: define-cmd(3, alias: "mitexmulticolumn", handle: (n, spec, it) => it),
+  multirow: define-glob-cmd("{,b}t{,b}t{,b}t", "mitexmultirow", handle: (..args) => args.pos().last()),
   hline: ignore-sym,
   vline: ignore-sym,
+  endfirsthead: ignore-sym,
+  endhead: ignore-sym,
+  endfoot: ignore-sym,
+  endlastfoot: ignore-sym,
   // Spaces: \! \, \> \: \; \ \quad \qquad
   "!": define-sym("negthinspace", sym: h(-(3/18) * 1em)),
   negthinspace: of-sym(h(-(3/18) * 1em)),
@@ -114,6 +226,8 @@
   phantom: define-cmd(1, handle: hide),
   hphantom: define-cmd(1, handle: it => box(height: 0pt, hide(it))),
   vphantom: define-cmd(1, handle: it => box(width: 0pt, hide(it))),
+  // \@ before a period, which only adjusts the space after it
+  "@": ignore-sym,
   // Escape symbols
   "_": define-sym("\\_"),
   "^": define-sym("hat"),
@@ -151,6 +265,15 @@
   frak: greedy-handle("mitexfrak", math.frak),
   tt: greedy-handle("mitexmono", math.mono),
   cal: greedy-handle("mitexcal", math.cal),
+  em: define-greedy-cmd("mitexitalic"),
+  sc: define-greedy-cmd("mitexupright"),
+  bfseries: define-greedy-cmd("mitexbold"),
+  mdseries: define-greedy-cmd("mitexupright"),
+  itshape: define-greedy-cmd("mitexitalic"),
+  upshape: define-greedy-cmd("mitexupright"),
+  scshape: define-greedy-cmd("mitexupright"),
+  sffamily: define-greedy-cmd("mitexsans"),
+  ttfamily: define-greedy-cmd("mitexmono"),
   bold: define-cmd(1, alias: "bold"),
   mathbf: define-cmd(1, alias: "mitexmathbf", handle: it => math.bold(math.upright(it))),
   bm: define-cmd(1, alias: "bold"),
@@ -189,17 +312,17 @@
   Bigr: define-cmd(1, alias: "Big"),
   biggr: define-cmd(1, alias: "bigg"),
   Biggr: define-cmd(1, alias: "Bigg"),
-  // todo: size, especially multi-line, ignore it for now.
-  Huge: ignore-sym,
-  normalsize: ignore-sym,
-  huge: ignore-sym,
-  small: ignore-sym,
-  footnotesize: ignore-sym,
-  Large: ignore-sym,
-  LARGE: ignore-sym,
-  scriptsize: ignore-sym,
-  large: ignore-sym,
-  tiny: ignore-sym,
+  // sizes, which only take effect in text mode
+  tiny: define-greedy-cmd("mitexsize", handle: it => it),
+  scriptsize: define-greedy-cmd("mitexsize"),
+  footnotesize: define-greedy-cmd("mitexsize"),
+  small: define-greedy-cmd("mitexsize"),
+  normalsize: define-greedy-cmd("mitexsize"),
+  large: define-greedy-cmd("mitexsize"),
+  Large: define-greedy-cmd("mitexsize"),
+  LARGE: define-greedy-cmd("mitexsize"),
+  huge: define-greedy-cmd("mitexsize"),
+  Huge: define-greedy-cmd("mitexsize"),
   // Colors
   color: define-greedy-cmd("mitexcolor", handle: (texcolor, ..args) => {
     let color = get-tex-color(texcolor)
@@ -209,22 +332,35 @@
       args.pos().sum()
     }
   }),
-  textcolor: define-cmd(2, alias: "colortext", handle: (texcolor, body) => {
+  textcolor: define-glob-cmd("{,b}tt", "colortext", handle: (..args) => {
+    let (texcolor, body) = args.pos().slice(-2)
     let color = get-tex-color(texcolor)
     if color != none {
-      text(fill: get-tex-color(texcolor), body)
+      text(fill: color, body)
     } else {
       body
     }
   }),
-  colorbox: define-cmd(2, handle: (texcolor, body) => {
+  colorbox: define-glob-cmd("{,b}tt", "colorbox", handle: (..args) => {
+    let (texcolor, body) = args.pos().slice(-2)
     let color = get-tex-color(texcolor)
     if color != none {
-      box(fill: get-tex-color(texcolor), $body$)
+      box(fill: color, $body$)
     } else {
       body
     }
   }),
+  fcolorbox: define-glob-cmd("{,b}ttt", "fcolorbox", handle: (..args) => {
+    let (frame, texcolor, body) = args.pos().slice(-3)
+    let color = get-tex-color(texcolor)
+    if color != none {
+      box(fill: color, stroke: get-tex-color(frame), $body$)
+    } else {
+      body
+    }
+  }),
+  definecolor: define-glob-cmd("{,b}ttt", "mitexdefinecolor", handle: ignore-me),
+  colorlet: define-glob-cmd("{,b}tt", "mitexdefinecolor"),
   // Limits
   limits: left1-op("limits"),
   nolimits: left1-op("scripts"),
@@ -988,6 +1124,12 @@
   Bmatrix: matrix-handle(delim: "{"),
   vmatrix: matrix-handle(delim: "|"),
   Vmatrix: matrix-handle(delim: "||"),
+  "matrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "matrix"),
+  "pmatrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "pmatrix"),
+  "bmatrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "bmatrix"),
+  "Bmatrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "Bmatrix"),
+  "vmatrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "vmatrix"),
+  "Vmatrix*": define-glob-env("{,b}", kind: "is-matrix", alias: "Vmatrix"),
   smallmatrix: matrix-handle(handle: (..args) => math.inline(math.mat.with(delim: none, ..args))),
   array: define-env(1, kind: "is-matrix", alias: "mitexarray", handle: (arg0: ("l",), ..args) => {
     if args.pos().len() == 0 {
@@ -1020,15 +1162,26 @@
   }),
   subarray: define-env(1, kind: "is-matrix", alias: "mitexarray"),
   // Environments
-  aligned: define-env(none, kind: "is-math", alias: "aligned", handle: call-or-ignore(it => pad(y: 0.2em, block(math.op(math.display(it)))))),
-  alignedat: define-env(1, kind: "is-math", alias: "alignedat", handle: (arg0: none, it) => pad(y: 0.2em, block(math.op(it)))),
-  align: define-env(none, kind: "is-math", alias: "aligned"),
-  "align*": define-env(none, kind: "is-math", alias: "aligned"),
+  aligned: define-env(none, kind: "is-align", alias: "aligned", handle: call-or-ignore(it => pad(y: 0.2em, block(math.op(math.display(it)))))),
+  alignedat: define-env(1, kind: "is-align", alias: "alignedat", handle: (arg0: none, it) => pad(y: 0.2em, block(math.op(it)))),
+  align: define-env(none, kind: "is-align", alias: "aligned"),
+  "align*": define-env(none, kind: "is-align", alias: "aligned"),
+  alignat: define-env(1, kind: "is-align", alias: "alignedat"),
+  "alignat*": define-env(1, kind: "is-align", alias: "alignedat"),
+  flalign: define-env(none, kind: "is-align", alias: "aligned"),
+  "flalign*": define-env(none, kind: "is-align", alias: "aligned"),
+  eqnarray: define-env(none, kind: "is-align", alias: "aligned"),
+  "eqnarray*": define-env(none, kind: "is-align", alias: "aligned"),
   equation: define-env(none, kind: "is-math", alias: "aligned"),
   "equation*": define-env(none, kind: "is-math", alias: "aligned"),
-  split: define-env(none, kind: "is-math", alias: "aligned"),
-  gather: define-env(none, kind: "is-math", alias: "aligned"),
-  gathered: define-env(none, kind: "is-math", alias: "aligned"),
+  split: define-env(none, kind: "is-align", alias: "aligned"),
+  gather: define-env(none, kind: "is-align", alias: "aligned"),
+  "gather*": define-env(none, kind: "is-align", alias: "aligned"),
+  gathered: define-env(none, kind: "is-align", alias: "aligned"),
+  multline: define-env(none, kind: "is-align", alias: "aligned"),
+  "multline*": define-env(none, kind: "is-align", alias: "aligned"),
+  intertext: define-cmd(1, alias: "mitexintertext", handle: ignore-me),
+  shortintertext: define-cmd(1, alias: "mitexintertext", handle: ignore-me),
   cases: define-env(none, kind: "is-cases", alias: "cases"),
   rcases: define-env(none, kind: "is-cases", alias: "rcases", handle: math.cases.with(reverse: true)),
   // Specials
@@ -1060,6 +1213,9 @@
   textup: define-cmd(1, alias: "#textup", handle: math.upright),
   textsf: define-cmd(1, alias: "#textsf", handle: math.sans),
   texttt: define-cmd(1, alias: "#texttt", handle: math.mono),
+  textsc: define-cmd(1, alias: "#smallcaps"),
+  textsuperscript: define-cmd(1, alias: "#super"),
+  textsubscript: define-cmd(1, alias: "#sub"),
   over: define-infix-cmd("frac"),
   atop: define-infix-cmd("atop", handle: (a, b) => $mat(delim: #none, #a; #b)$),
   choose: define-infix-cmd("binom", handle: math.binom),
@@ -1114,7 +1270,8 @@
   ExecuteOptions: ignore-sym,
   RequirePackage: ignore-sym,
   RequirePackageWithOptions: ignore-sym,
-  documentclass: ignore-sym,
+  documentclass: define-glob-cmd("{,b}t", "mitexdocumentclass", handle: ignore-me),
+  usepackage: define-glob-cmd("{,b}t", "mitexusepackage", handle: ignore-me),
   PassOptionsToClass: ignore-sym,
   PassOptionsToPackage: ignore-sym,
   IfFileExists: ignore-sym,
@@ -1134,7 +1291,6 @@
   newcounter: ignore-sym,
   newlength: ignore-sym,
   newsavebox: ignore-sym,
-  newtheorem: ignore-sym,
   newfont: ignore-sym,
   ProvidesClass: ignore-sym,
   LoadClass: ignore-sym,
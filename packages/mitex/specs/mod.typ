@@ -6,7 +6,287 @@
 #let packages = (latex-std,)
 #let mitex-scope = packages.map(pkg => pkg.scope).sum()
 
-// 2. export all packages with specs by metadata and <mitex-packages> label,
+// 2. helpers referred by the converted code
+// the placeholder for LaTeX code that mitex fails to convert in the
+// error-recovering mode
+#let mitex-error(msg) = highlight(fill: red.lighten(60%), raw(msg))
+// the title of a heading with a short title for the outline, e.g.
+// `\section[short]{long}`, which is shown in the outline if it is laid out
+// after an outline and before the first outlined heading following it
+#let mitex-short-title(short, long) = context {
+  let pos = here().position()
+  let before(a, b) = a.page < b.page or (a.page == b.page and a.y < b.y)
+  let in-outline = query(selector(outline).before(here())).any(it => {
+    let next = query(selector(heading.where(outlined: true)).after(it.location()))
+    next.len() == 0 or before(pos, next.first().location().position())
+  })
+  if in-outline { short } else { long }
+}
+// the rows of an alignment environment like `align`, each of them is an
+// equation numbered by its own, and the cells are aligned across rows by
+// measuring them. A row is either `(cells: .., numbered: .., tag: .., label: ..)`
+// or `(intertext: ..)`.
+#let mitex-align(..rows, aligns: auto) = context {
+  let rows = rows.pos()
+  let cols = calc.max(1, ..rows.map(row => row.at("cells", default: ()).len()))
+  let aligns = if aligns == auto {
+    range(cols).map(i => if calc.even(i) { right } else { left })
+  } else {
+    aligns
+  }
+  let display(cell) = math.equation(block: false, math.display(cell.body))
+  let widths = range(cols).map(i => calc.max(0pt, ..rows.map(row => {
+    let cells = row.at("cells", default: ())
+    if i < cells.len() { measure(display(cells.at(i))).width } else { 0pt }
+  })))
+  for row in rows {
+    if "intertext" in row {
+      block(row.intertext)
+      continue
+    }
+    let body = row.cells.enumerate().map(((i, cell)) => {
+      box(width: widths.at(i), align(aligns.at(i, default: left), display(cell)))
+    }).join()
+    let tag = row.at("tag", default: none)
+    let args = if tag != none {
+      (numbering: _ => tag)
+    } else if not row.at("numbered", default: true) {
+      (numbering: none)
+    } else {
+      (:)
+    }
+    let eq = math.equation(block: true, ..args, body)
+    let label = row.at("label", default: none)
+    if label != none { [#eq#label] } else { eq }
+    // a tagged row takes no number
+    if tag != none { counter(math.equation).update(n => n - 1) }
+  }
+}
+// a theorem-like environment declared by `\newtheorem`, which is a figure of
+// the kind of the counter it shares, shown in the `style` of amsthm, i.e.
+// "plain", "definition" or "remark". If numbered `within` a heading level, the
+// number is prefixed by the numbers of the outlined headings and restarts
+// after each heading at or above that level.
+#let mitex-theorem(kind: "theorem", supplement: none, numbered: true, within: none, style: "plain", title: none, label: none, body) = {
+  let pattern = if not numbered {
+    none
+  } else if within == none {
+    "1"
+  } else {
+    _ => {
+      let nums = (0,) * within
+      let start = none
+      for it in query(selector(heading.where(outlined: true)).before(here())) {
+        if it.level <= within {
+          nums = nums.slice(0, it.level - 1) + (nums.at(it.level - 1) + 1,) + (0,) * (within - it.level)
+          start = it.location()
+        }
+      }
+      let theorems = selector(figure.where(kind: kind))
+      if start != none { theorems = theorems.after(start) }
+      numbering("1.1", ..nums, query(theorems.before(here())).len())
+    }
+  }
+  show figure.where(kind: kind): it => block(width: 100%, breakable: true, align(left, {
+    let head = it.supplement
+    if it.numbering != none { head += [ ] + it.counter.display(it.numbering) }
+    if style == "remark" { emph(head) } else { strong(head) }
+    if title != none [ (#title)]
+    if style == "remark" [.] else { strong[.] }
+    h(0.5em)
+    if style == "plain" { emph(it.body) } else { it.body }
+  }))
+  let fig = figure(kind: kind, supplement: supplement, numbering: pattern, outlined: false, body)
+  if label != none [#fig#label] else { fig }
+}
+// the proof of amsthm, whose title is "Proof" by default, ended by a QED
+// marker at the end of its last line
+#let mitex-proof(title: none, body) = block(width: 100%, breakable: true, {
+  emph(if title == none [Proof] else { title })
+  [.]
+  h(0.5em)
+  body
+  h(1fr)
+  "□"
+})
+// the title block of `\maketitle` like in the article class, where each
+// author is `(name: .., affiliation: ..)` and placed side by side
+#let mitex-title(title: none, authors: (), date: none) = align(center, {
+  if title != none {
+    block(below: 1.5em, text(1.7em, title))
+  }
+  if authors.len() > 0 {
+    grid(
+      columns: calc.min(authors.len(), 3) * (1fr,),
+      gutter: 1em,
+      ..authors.map(author => {
+        text(1.2em, author.name)
+        let affiliation = author.at("affiliation", default: none)
+        if affiliation != none [ \ #affiliation]
+      }),
+    )
+  }
+  if date != none {
+    block(above: 1.2em, text(1.2em, date))
+  }
+  v(1em)
+})
+// the abstract with a centered title, set narrower and smaller than the text
+#let mitex-abstract(body) = block(width: 100%, inset: (x: 2em), {
+  align(center, strong[Abstract])
+  set text(0.9em)
+  set par(justify: true)
+  body
+})
+// the templates of the document classes, called by the document mode like
+// `#show: mitex-article.with(..)`. Each author is
+// `(name: .., affiliations: (..), thanks: (..))`, and the options of the class
+// are given as `paper`, `font-size`, `columns` and `flipped`.
+#let std-columns = columns
+// the authors for `mitex-title`, with the thanks as footnotes of the names
+#let title-authors(authors) = authors.map(author => (
+  name: author.name + author.at("thanks", default: ()).map(footnote).join(),
+  affiliation: author.at("affiliations", default: ()).join(linebreak()),
+))
+// the template of the article class, with the title block at the top
+#let mitex-article(title: none, authors: (), date: none, abstract: none, keywords: (), paper: "us-letter", font-size: 10pt, columns: 1, flipped: false, body) = {
+  set page(paper: paper, flipped: flipped, numbering: "1")
+  set text(size: font-size)
+  set par(justify: true)
+  set heading(numbering: "1.1")
+  if title != none or authors.len() > 0 {
+    mitex-title(title: title, authors: title-authors(authors), date: date)
+  }
+  if abstract != none { mitex-abstract(abstract) }
+  if columns > 1 { std-columns(columns, gutter: 1.5em, body) } else { body }
+}
+// the template of the report class, with the title block and the abstract on
+// their own pages, and each chapter, i.e. a level 1 heading, on a new page
+#let mitex-report(title: none, authors: (), date: none, abstract: none, keywords: (), paper: "us-letter", font-size: 10pt, columns: 1, flipped: false, body) = {
+  set page(paper: paper, flipped: flipped)
+  set text(size: font-size)
+  set par(justify: true)
+  set heading(numbering: "1.1")
+  show heading.where(level: 1): it => {
+    pagebreak(weak: true)
+    set text(1.5em)
+    block(below: 1.5em, {
+      if it.numbering != none [Chapter #counter(heading).display(it.numbering) \ ]
+      it.body
+    })
+  }
+  if title != none or authors.len() > 0 {
+    page(align(horizon, mitex-title(title: title, authors: title-authors(authors), date: date)))
+  }
+  if abstract != none {
+    page(align(horizon, mitex-abstract(abstract)))
+  }
+  // the chapters break pages, which is not allowed inside `columns`
+  set page(numbering: "1", columns: columns)
+  counter(page).update(1)
+  body
+}
+// the template of the IEEEtran class, in two columns by default, where the
+// keywords are the index terms following the abstract
+#let mitex-ieee(title: none, authors: (), date: none, abstract: none, keywords: (), paper: "us-letter", font-size: 10pt, columns: 2, flipped: false, body) = {
+  set text(font: "STIX Two Text", size: font-size)
+  set page(
+    paper: paper,
+    flipped: flipped,
+    // the margins depend on the paper size
+    margin: if paper == "a4" {
+      (x: 41.5pt, top: 80.51pt, bottom: 89.51pt)
+    } else {
+      (x: (50pt / 216mm) * 100%, top: (55pt / 279mm) * 100%, bottom: (64pt / 279mm) * 100%)
+    },
+  )
+  set math.equation(numbering: "(1)")
+  show math.equation: set block(spacing: 0.65em)
+  set enum(indent: 10pt, body-indent: 9pt)
+  set list(indent: 10pt, body-indent: 9pt)
+
+  // sections are centered smallcaps like "I. Introduction", subsections are
+  // italic like "A. Background", and the deeper ones are run-ins
+  set heading(numbering: "I.A.1.")
+  show heading: it => context {
+    let levels = counter(heading).get()
+    let deepest = if levels != () { levels.last() } else { 1 }
+    set text(font-size, weight: 400)
+    if it.level == 1 {
+      let is-ack = it.body in ([Acknowledgment], [Acknowledgement], [Acknowledgments], [Acknowledgements])
+      set align(center)
+      set text(if is-ack { font-size } else { font-size * 1.2 })
+      show: smallcaps
+      v(20pt, weak: true)
+      if it.numbering != none and not is-ack {
+        numbering("I.", deepest)
+        h(7pt, weak: true)
+      }
+      it.body
+      v(13.75pt, weak: true)
+    } else if it.level == 2 {
+      set par(first-line-indent: 0pt)
+      set text(style: "italic")
+      v(10pt, weak: true)
+      if it.numbering != none {
+        numbering("A.", deepest)
+        h(7pt, weak: true)
+      }
+      it.body
+      v(10pt, weak: true)
+    } else {
+      if it.level == 3 and it.numbering != none [#numbering("1)", deepest) ]
+      emph[#it.body:]
+    }
+  }
+
+  v(3pt, weak: true)
+  if title != none { align(center, text(font-size * 1.8, title)) }
+  v(8.35mm, weak: true)
+  // the authors in rows of at most 3
+  for i in range(calc.ceil(authors.len() / 3)) {
+    let end = calc.min((i + 1) * 3, authors.len())
+    let row = authors.slice(i * 3, end)
+    grid(
+      columns: row.len() * (1fr,),
+      gutter: 12pt,
+      ..row.map(author => align(center, {
+        text(font-size * 1.2, author.name + author.at("thanks", default: ()).map(footnote).join())
+        for affiliation in author.at("affiliations", default: ()) [ \ #emph(affiliation)]
+      })),
+    )
+    if end < authors.len() { v(16pt, weak: true) }
+  }
+  v(40pt, weak: true)
+
+  show: body => if columns > 1 { std-columns(columns, gutter: 12pt, body) } else { body }
+  set par(justify: true, first-line-indent: 1em)
+  show par: set block(spacing: 0.65em)
+  if abstract != none {
+    set text(weight: 700)
+    [#h(1em) _Abstract_---#abstract]
+    if keywords.len() > 0 {
+      parbreak()
+      [#h(1em) _Index Terms_---#keywords.join(", ")]
+    }
+    v(2pt)
+  }
+  body
+}
+#let mitex-scope = mitex-scope + (
+  mitex-error: mitex-error,
+  mitex-short-title: mitex-short-title,
+  mitex-align: mitex-align,
+  mitex-theorem: mitex-theorem,
+  mitex-proof: mitex-proof,
+  mitex-title: mitex-title,
+  mitex-abstract: mitex-abstract,
+  mitex-article: mitex-article,
+  mitex-report: mitex-report,
+  mitex-ieee: mitex-ieee,
+)
+
+// 3. export all packages with specs by metadata and <mitex-packages> label,
 //    mitex-cli can fetch them by
 //    `typst query --root . ./packages/mitex/specs/mod.typ "<mitex-packages>"`
 #metadata(packages) <mitex-packages>
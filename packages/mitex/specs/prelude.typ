@@ -66,6 +66,7 @@
 /// - Bracket/b: []
 /// - Parenthesis/p: ()
 /// - Term/t: any rest of terms, typically {} or single char
+/// - Verbatim/v: raw text in {} or between a pair of the same char, like `\verb|a_b|`
 ///
 /// Arguments:
 /// - pat (pattern): The pattern for glob-cmd
@@ -120,8 +121,8 @@
 /// - alias (str): Alias command for typst handler.
 ///   For example, alias `\begin{alignedat}{2}` to typst's `alignedat`,
 ///   and alias `\begin{aligned}` to typst's `aligned`, as the key in mitex-scope.
-/// - kind (str): environment kind, it could be "is-math", "is-cases", "is-matrix",
-///   "is-itemize", "is-enumerate"
+/// - kind (str): environment kind, it could be "is-math", "is-align", "is-cases",
+///   "is-matrix", "is-itemize", "is-enumerate", "is-description", "is-verbatim"
 /// - handle (function): The handler function, as the value of alias in mitex-scope.
 ///   It receives fixed number of named arguments as environment options,
 ///   for example `alignedat(arg0: ..)` or `alignedat(arg0: .., arg1: ..)`.